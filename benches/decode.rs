@@ -35,6 +35,34 @@ fn decode(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn decode_width_7(b: &mut Bencher) {
+    let mut buf = test::black_box(vec![u8::default(); 42]);
+
+    b.iter(|| {
+        let reader = File::open("tests/data/width_7.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        decoder.decode(&mut buf).unwrap();
+        buf.fill_with(u8::default);
+    });
+}
+
+#[bench]
+fn decode_width_14(b: &mut Bencher) {
+    let mut buf = test::black_box(vec![u8::default(); 168]);
+
+    b.iter(|| {
+        let reader = File::open("tests/data/width_14.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        decoder.decode(&mut buf).unwrap();
+        buf.fill_with(u8::default);
+    });
+}
+
 #[bench]
 fn decode_to_vec(b: &mut Bencher) {
     b.iter(|| {
@@ -46,6 +74,34 @@ fn decode_to_vec(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn decode_into_vec(b: &mut Bencher) {
+    let mut buf = test::black_box(Vec::with_capacity(87616));
+
+    b.iter(|| {
+        let reader = File::open("tests/data/qr_code.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        decoder.decode_into_vec(&mut buf).unwrap();
+    });
+}
+
+#[cfg(feature = "rayon")]
+#[bench]
+fn decode_parallel(b: &mut Bencher) {
+    let mut buf = test::black_box(vec![u8::default(); 87616]);
+
+    b.iter(|| {
+        let reader = File::open("tests/data/qr_code.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        decoder.decode_parallel(&mut buf).unwrap();
+        buf.fill_with(u8::default);
+    });
+}
+
 #[bench]
 fn name(b: &mut Bencher) {
     let reader = File::open("tests/data/qr_code.xbm")
@@ -113,11 +169,8 @@ fn read_image(b: &mut Bencher) {
     });
 }
 
-#[cfg(feature = "image")]
 #[bench]
 fn dimensions(b: &mut Bencher) {
-    use image::ImageDecoder;
-
     let reader = File::open("tests/data/qr_code.xbm")
         .map(BufReader::new)
         .unwrap();
@@ -25,6 +25,7 @@ fn encode(b: &mut Bencher) {
     let mut pixels = image::open("tests/data/qr_code.png")
         .map(DynamicImage::into_bytes)
         .unwrap();
+    #[allow(clippy::needless_for_each)]
     pixels
         .iter_mut()
         .for_each(|p| *p = u8::from(*p <= (u8::MAX / 2)));
@@ -0,0 +1,54 @@
+// SPDX-FileCopyrightText: 2024 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! An example of printing the information of a XBM file.
+
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use anyhow::Context;
+use clap::Parser;
+use xbm::{decode::Format, Decoder};
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Opt {
+    /// Input XBM file.
+    #[arg(value_name("INFILE"))]
+    input: PathBuf,
+}
+
+fn main() -> anyhow::Result<()> {
+    let opt = Opt::parse();
+
+    let reader = File::open(&opt.input)
+        .map(BufReader::new)
+        .with_context(|| format!("could not open {}", opt.input.display()))?;
+    let decoder = Decoder::new(reader).context("could not create new XBM decoder")?;
+    let header = decoder.metadata();
+    let format = decoder.format();
+    decoder.validate().context("could not validate XBM image")?;
+
+    let (width, height) = (header.width(), header.height());
+    // Each row is padded to a multiple of the element size of the format.
+    let element_bits = match format {
+        Format::X10 => 16,
+        Format::X11 => 8,
+    };
+    let bytes = ((u64::from(width) + element_bits - 1) / element_bits)
+        * (element_bits / 8)
+        * u64::from(height);
+    println!("Name: {}", header.name());
+    println!("Dimensions: {width}x{height}");
+    if let Some((x, y)) = header.hotspot() {
+        println!("Hotspot: ({x}, {y})");
+    } else {
+        println!("Hotspot: none");
+    }
+    match format {
+        Format::X10 => println!("Format: X10"),
+        Format::X11 => println!("Format: X11"),
+    }
+    println!("Bytes: {bytes}");
+    Ok(())
+}
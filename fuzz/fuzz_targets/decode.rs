@@ -0,0 +1,37 @@
+// SPDX-FileCopyrightText: 2024 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![no_main]
+
+use std::io::Cursor;
+
+use libfuzzer_sys::fuzz_target;
+use xbm::{decode::Limits, Decoder};
+
+fuzz_target!(|data: &[u8]| {
+    // Keep the allocations small so that the fuzzer does not run out of memory.
+    let limits = Limits {
+        max_pixels: 1 << 20,
+        ..Default::default()
+    };
+
+    if let Ok(decoder) = Decoder::with_limits(Cursor::new(data), limits) {
+        let _ = decoder.decode_to_vec();
+    }
+    if let Ok(decoder) = Decoder::with_limits(Cursor::new(data), limits) {
+        let len = decoder
+            .pixel_count()
+            .and_then(|count| usize::try_from(count).ok())
+            .expect("the number of pixels should be within the limits");
+        let mut buf = vec![u8::default(); len];
+        let _ = decoder.decode(&mut buf);
+    }
+    if let Ok(decoder) = Decoder::with_limits(Cursor::new(data), limits) {
+        let _ = decoder.decode_packed();
+    }
+    if let Ok(decoder) = Decoder::with_limits(Cursor::new(data), limits) {
+        let _ = decoder.set_pixels().count();
+    }
+    let _ = xbm::lint(Cursor::new(data));
+});
@@ -0,0 +1,57 @@
+// SPDX-FileCopyrightText: 2024 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! An implementation of CRC-32 (ISO-HDLC).
+
+/// CRC-32 (ISO-HDLC) hasher.
+#[derive(Clone, Copy, Debug)]
+pub struct Crc32(u32);
+
+impl Crc32 {
+    /// The reversed polynomial.
+    const POLY: u32 = 0xEDB8_8320;
+
+    /// Creates a new `Crc32`.
+    pub const fn new() -> Self {
+        Self(u32::MAX)
+    }
+
+    /// Processes a byte.
+    pub fn update(&mut self, byte: u8) {
+        self.0 ^= u32::from(byte);
+        for _ in 0..8 {
+            self.0 = if self.0 & 1 == 1 {
+                (self.0 >> 1) ^ Self::POLY
+            } else {
+                self.0 >> 1
+            };
+        }
+    }
+
+    /// Returns the checksum of the processed bytes.
+    pub const fn finalize(self) -> u32 {
+        !self.0
+    }
+
+    /// Computes the checksum of `bytes`.
+    pub fn checksum(bytes: impl IntoIterator<Item = u8>) -> u32 {
+        let mut hasher = Self::new();
+        for byte in bytes {
+            hasher.update(byte);
+        }
+        hasher.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum() {
+        assert_eq!(Crc32::checksum(*b""), 0x0000_0000);
+        assert_eq!(Crc32::checksum(*b"123456789"), 0xCBF4_3926);
+        assert_eq!(Crc32::checksum(*b"a"), 0xE8B7_BE43);
+    }
+}
@@ -5,165 +5,587 @@
 //! Decodes XBM images.
 
 use std::{
+    borrow::Cow,
     error, fmt,
-    io::{self, BufRead, Seek, SeekFrom},
-    num::ParseIntError,
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor, Seek, SeekFrom},
+    iter::{self, FusedIterator},
+    mem,
+    num::{NonZeroU32, ParseIntError},
+    path::Path,
+    str::SplitWhitespace,
 };
 
+use crate::{crc32::Crc32, BitOrder};
+
+/// The byte order mark, which some editors write at the start of a file.
+const BOM: char = '\u{FEFF}';
+
 /// Decoder for XBM images.
+///
+/// The image is read line by line with [`BufRead::read_line`], which always
+/// reads up to the next newline regardless of how the underlying reader
+/// delivers the data, so a hex byte value is never split by short reads. A
+/// newline always terminates a hex byte value; the hex byte value split by a
+/// newline (e.g., `0x1` at the end of a line and `C` at the start of the next
+/// line) is rejected as an invalid hex byte value. This also applies when
+/// the array is read incrementally with [`Decoder::stream_array`].
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug)]
 pub struct Decoder<R: BufRead + Seek> {
     reader: R,
-    name: String,
-    width: u32,
-    height: u32,
-    x_hot: Option<u32>,
-    y_hot: Option<u32>,
+    header: Header,
+    verify_crc32: bool,
+    verify_row_length: bool,
+    stream_array: bool,
+    invert: bool,
+    bit_order: BitOrder,
+    format: Format,
+    leading_comment: Option<String>,
+    extra_defines: Vec<(String, String)>,
+    offset: usize,
 }
 
 impl<R: BufRead + Seek> Decoder<R> {
+    /// Creates a new `Decoder`.
+    ///
+    /// This uses the default [`Limits`]. Use [`Decoder::with_limits`] to
+    /// change them. Use [`Decoder::from_bytes`] to decode the image from a
+    /// byte slice.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - The header is invalid.
+    /// - Only one of the hotspot coordinates is defined.
+    /// - The hotspot is defined before the width or the height.
+    /// - The same `#define` directive is defined more than once.
+    /// - Either the width or the height is `0`.
+    /// - The image dimensions exceed the default limits.
+    /// - Either the width or the height exceeds the range of [`usize`].
+    /// - An error occurs during I/O operations.
+    /// - An error occurs while parsing either the width, the height, the
+    ///   hotspot, or the checksum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// assert!(Decoder::new(reader).is_ok());
+    /// ```
+    #[inline]
+    pub fn new(reader: R) -> Result<Self, Error> {
+        Self::with_limits(reader, Limits::default())
+    }
+
+    /// Creates a new `Decoder` which expects the name of the image to be
+    /// `expected`.
+    ///
+    /// This is useful when the name is used as a fixed symbol name, for
+    /// example, in later C compilation. This uses the default [`Limits`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the name of the image is different from `expected`,
+    /// or if [`Decoder::new`] returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::{decode::Error, Decoder};
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// assert!(Decoder::new_with_name(reader, "image").is_ok());
+    ///
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// assert!(matches!(
+    ///     Decoder::new_with_name(reader, "cursor").unwrap_err(),
+    ///     Error::UnexpectedName { .. }
+    /// ));
+    /// ```
+    pub fn new_with_name(reader: R, expected: impl AsRef<str>) -> Result<Self, Error> {
+        let inner = |reader: R, expected: &str| -> Result<Self, Error> {
+            let decoder = Self::new(reader)?;
+            if decoder.name() != expected {
+                return Err(Error::UnexpectedName {
+                    expected: expected.into(),
+                    found: decoder.name().into(),
+                });
+            }
+            Ok(decoder)
+        };
+        inner(reader, expected.as_ref())
+    }
+
     #[allow(
         clippy::cognitive_complexity,
         clippy::missing_panics_doc,
         clippy::too_many_lines
     )]
-    /// Creates a new `Decoder`.
+    /// Creates a new `Decoder` with the limits on the image dimensions.
+    ///
+    /// The declared image dimensions are checked against `limits` before any
+    /// allocation for the image happens.
+    ///
+    /// A leading UTF-8 byte order mark is ignored.
+    /// Comments before the first `#define` directive are skipped, and can be
+    /// retrieved with [`Decoder::leading_comment`].
+    /// Comments after the values of the `#define` directives are ignored.
+    /// The `#define` directives which start with the name of the image but
+    /// are not recognized are skipped, and can be retrieved with
+    /// [`Decoder::extra_defines`].
     ///
     /// # Errors
     ///
     /// Returns [`Err`] if any of the following are true:
     ///
     /// - The header is invalid.
+    /// - Only one of the hotspot coordinates is defined.
+    /// - The hotspot is defined before the width or the height.
+    /// - The same `#define` directive is defined more than once.
+    /// - Either the width or the height is `0`.
+    /// - The image dimensions exceed `limits`.
+    /// - Either the width or the height exceeds the range of [`usize`].
     /// - An error occurs during I/O operations.
-    /// - An error occurs while parsing either the width, the height, or the
-    ///   hotspot.
+    /// - An error occurs while parsing either the width, the height, the
+    ///   hotspot, or the checksum.
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::{fs::File, io::BufReader};
     /// #
-    /// # use xbm::Decoder;
+    /// # use xbm::{
+    /// #     decode::{Error, Limits},
+    /// #     Decoder,
+    /// # };
     /// #
+    /// let limits = Limits {
+    ///     max_width: 4,
+    ///     ..Default::default()
+    /// };
     /// let reader = File::open("tests/data/basic.xbm")
     ///     .map(BufReader::new)
     ///     .unwrap();
-    /// assert!(Decoder::new(reader).is_ok());
+    /// assert!(matches!(
+    ///     Decoder::with_limits(reader, limits).unwrap_err(),
+    ///     Error::LimitsExceeded
+    /// ));
     /// ```
-    pub fn new(mut reader: R) -> Result<Self, Error> {
+    pub fn with_limits(mut reader: R, limits: Limits) -> Result<Self, Error> {
+        let mut line_no = 1;
         let mut buf = String::new();
-        reader.read_line(&mut buf)?;
-        let mut tokens = buf.split_whitespace();
-        if tokens.next() != Some("#define") {
-            return Err(Error::InvalidHeader);
+        if reader.read_line(&mut buf)? == 0 {
+            return Err(invalid_header(line_no, &buf));
+        }
+        if buf.starts_with(BOM) {
+            buf.drain(..BOM.len_utf8());
+        }
+        let mut comment = String::new();
+        let mut in_comment = false;
+        loop {
+            let line = buf.trim();
+            if in_comment || line.starts_with("/*") {
+                let start = if in_comment { 0 } else { 2 };
+                in_comment = match line[start..].find("*/") {
+                    Some(end) if line[start + end + 2..].trim().is_empty() => false,
+                    Some(_) => return Err(invalid_header(line_no, &buf)),
+                    None => true,
+                };
+                comment.push_str(&buf);
+            } else if line.starts_with("//") || (line.is_empty() && !comment.is_empty()) {
+                comment.push_str(&buf);
+            } else if !line.is_empty() {
+                break;
+            }
+            line_no += 1;
+            buf.clear();
+            if reader.read_line(&mut buf)? == 0 {
+                return Err(invalid_header(line_no, &buf));
+            }
+        }
+        let leading_comment = (!comment.is_empty()).then(|| comment.trim_end().to_owned());
+        let code = strip_comments(&buf);
+        let Some(tokens) = split_define(&code) else {
+            return Err(invalid_header(line_no, &buf));
+        };
+        let mut tokens = tokens.peekable();
+        if tokens.peek().is_some_and(|t| is_hotspot_key(t)) {
+            return Err(Error::HotspotBeforeDimensions);
         }
         let Some(name) = tokens
             .next()
-            .filter(|t| t.ends_with("_width"))
-            .map(|t| t.trim_end_matches("_width"))
+            .and_then(|t| t.strip_suffix("_width"))
             .filter(|n| {
                 let mut chars = n.chars();
                 chars.next().is_some_and(unicode_ident::is_xid_start)
                     && chars.all(unicode_ident::is_xid_continue)
             })
         else {
-            return Err(Error::InvalidHeader);
-        };
-        let Some(width) = tokens.next().map(str::parse).transpose()? else {
-            return Err(Error::InvalidHeader);
-        };
-        if tokens.next().is_some() {
-            return Err(Error::InvalidHeader);
-        }
-
-        let mut buf = String::new();
-        reader.read_line(&mut buf)?;
-        let mut tokens = buf.split_whitespace();
-        if tokens.next() != Some("#define") || tokens.next() != Some(&format!("{name}_height")) {
-            return Err(Error::InvalidHeader);
-        }
-        let Some(height) = tokens.next().map(str::parse).transpose()? else {
-            return Err(Error::InvalidHeader);
+            return Err(invalid_header(line_no, &buf));
         };
-        if tokens.next().is_some() {
-            return Err(Error::InvalidHeader);
-        }
-
-        let pos = reader.stream_position()?;
+        let width = parse_define_value(tokens, line_no, &buf)?;
 
+        let mut extra_defines = Vec::new();
+        // `name` borrows from the first line, so the following lines are read
+        // into another buffer which is reused until the end of the header.
         let mut buf = String::new();
-        reader.read_line(&mut buf)?;
-        let mut tokens = buf.split_whitespace();
-        let x_hot = if tokens.next() == Some("#define") {
-            if tokens.next() != Some(&format!("{name}_x_hot")) {
-                return Err(Error::InvalidHeader);
-            }
-            let Some(value) = tokens.next().map(str::parse).transpose()? else {
-                return Err(Error::InvalidHeader);
+        let height = loop {
+            line_no += 1;
+            buf.clear();
+            reader.read_line(&mut buf)?;
+            let code = strip_comments(&buf);
+            let Some(mut tokens) = split_define(&code) else {
+                return Err(invalid_header(line_no, &buf));
             };
-            if tokens.next().is_some() {
-                return Err(Error::InvalidHeader);
+            match tokens.next() {
+                Some(key) if key.strip_prefix(name) == Some("_height") => {
+                    break parse_define_value(tokens, line_no, &buf)?;
+                }
+                Some(key) if key.strip_prefix(name) == Some("_width") => {
+                    return Err(Error::DuplicateDefine("width"));
+                }
+                Some(key) if is_hotspot_key(key) => return Err(Error::HotspotBeforeDimensions),
+                Some(key) if is_extra_key(key, name) => {
+                    extra_defines.push((key.into(), tokens.collect::<Vec<_>>().join(" ")));
+                }
+                _ => return Err(invalid_header(line_no, &buf)),
             }
-            Some(value)
-        } else {
-            reader.seek(SeekFrom::Start(pos))?;
-            Option::default()
         };
+        if width == 0 || height == 0 {
+            return Err(Error::ZeroDimension);
+        }
+        limits.check(width, height)?;
+        // The width and the height are converted to `usize` while decoding, so
+        // reject them here rather than panicking later.
+        if usize::try_from(width).is_err() || usize::try_from(height).is_err() {
+            return Err(Error::ImageTooLarge);
+        }
 
-        let mut buf = String::new();
-        reader.read_line(&mut buf)?;
-        let mut tokens = buf.split_whitespace();
-        let y_hot = if tokens.next() == Some("#define") {
-            if tokens.next() != Some(&format!("{name}_y_hot")) {
-                return Err(Error::InvalidHeader);
-            }
-            let Some(value) = tokens.next().map(str::parse).transpose()? else {
-                return Err(Error::InvalidHeader);
+        let mut x_hot = Option::default();
+        let mut y_hot = Option::default();
+        let mut crc32 = Option::default();
+        loop {
+            let pos = reader.stream_position()?;
+            buf.clear();
+            reader.read_line(&mut buf)?;
+            let code = strip_comments(&buf);
+            let Some(mut tokens) = split_define(&code) else {
+                reader.seek(SeekFrom::Start(pos))?;
+                break;
             };
-            if tokens.next().is_some() {
-                return Err(Error::InvalidHeader);
+            line_no += 1;
+            let Some(key) = tokens
+                .next()
+                .and_then(|t| t.strip_prefix(name))
+                .and_then(|t| t.strip_prefix('_'))
+            else {
+                return Err(invalid_header(line_no, &buf));
+            };
+            match key {
+                "width" => return Err(Error::DuplicateDefine("width")),
+                "height" => return Err(Error::DuplicateDefine("height")),
+                "x_hot" if x_hot.is_some() => return Err(Error::DuplicateDefine("x_hot")),
+                "y_hot" if y_hot.is_some() => return Err(Error::DuplicateDefine("y_hot")),
+                "crc32" if crc32.is_some() => return Err(Error::DuplicateDefine("crc32")),
+                "x_hot" if x_hot.is_none() && y_hot.is_none() && crc32.is_none() => {
+                    x_hot = Some(parse_define_value(tokens, line_no, &buf)?);
+                }
+                "y_hot" if y_hot.is_none() && crc32.is_none() => {
+                    y_hot = Some(parse_define_value(tokens, line_no, &buf)?);
+                }
+                "crc32" if crc32.is_none() => {
+                    let Some(value) = tokens.next().and_then(|v| v.strip_prefix("0x")) else {
+                        return Err(invalid_header(line_no, &buf));
+                    };
+                    crc32 = Some(u32::from_str_radix(value, 16)?);
+                    if tokens.next().is_some() {
+                        return Err(invalid_header(line_no, &buf));
+                    }
+                }
+                "" | "x_hot" | "y_hot" | "crc32" => return Err(invalid_header(line_no, &buf)),
+                _ => {
+                    let key = format!("{name}_{key}");
+                    extra_defines.push((key, tokens.collect::<Vec<_>>().join(" ")));
+                }
             }
-            Some(value)
-        } else {
-            reader.seek(SeekFrom::Start(pos))?;
-            Option::default()
-        };
+        }
 
         if x_hot.is_some() != y_hot.is_some() {
-            return Err(Error::InvalidHeader);
+            return Err(Error::IncompleteHotspot);
         }
 
-        let pos = reader.stream_position()?;
-        let mut buf = String::new();
-        reader.read_line(&mut buf)?;
-        if buf.starts_with(&format!("static unsigned char {name}_bits[] = {{"))
-            || buf.starts_with(&format!("static char {name}_bits[] = {{"))
-        {
-            let Some(index) = buf
-                .find('{')
-                .and_then(|i| i.checked_add(1))
-                .map(u64::try_from)
-                .transpose()
-                .ok()
-                .flatten()
-            else {
-                return Err(Error::InvalidHeader);
-            };
-            reader.seek(SeekFrom::Start(pos + index))?;
-        } else {
-            return Err(Error::InvalidHeader);
+        // Skip the lines which contain only comments, such as a banner before
+        // the array declaration, and the lines which define the type of the
+        // array.
+        let mut pos;
+        loop {
+            line_no += 1;
+            pos = reader.stream_position()?;
+            buf.clear();
+            if reader.read_line(&mut buf)? == 0 {
+                break;
+            }
+            let code = strip_comments(&buf);
+            if !code.trim().is_empty() && !is_type_definition(&code, name) {
+                break;
+            }
         }
-        let name = name.into();
-        Ok(Self {
-            reader,
-            name,
+        // A comment may follow the declaration, so look for the opening brace
+        // in the code. The byte offsets are preserved by `strip_comments`.
+        let (brace, format) = {
+            let code = strip_comments(&buf);
+            let brace = code.find('{');
+            let format = parse_declaration(&code[..brace.unwrap_or(code.len())], name);
+            (brace, format.ok_or_else(|| invalid_header(line_no, &buf))?)
+        };
+        let index = if let Some(index) = brace {
+            index
+        } else {
+            // The opening brace is on the next line, possibly after the lines
+            // which contain only comments.
+            loop {
+                line_no += 1;
+                pos = reader.stream_position()?;
+                buf.clear();
+                if reader.read_line(&mut buf)? == 0 {
+                    return Err(invalid_header(line_no, &buf));
+                }
+                let code = strip_comments(&buf);
+                match code.find('{') {
+                    Some(index) if code[..index].trim().is_empty() => break index,
+                    None if code.trim().is_empty() => {}
+                    _ => return Err(invalid_header(line_no, &buf)),
+                }
+            }
+        };
+        let index = u64::try_from(index + 1).map_err(|_| invalid_header(line_no, &buf))?;
+        reader.seek(SeekFrom::Start(pos + index))?;
+        let offset = usize::try_from(pos + index).map_err(|_| invalid_header(line_no, &buf))?;
+        let header = Header {
+            name: name.into(),
             width,
             height,
             x_hot,
             y_hot,
+            crc32,
+        };
+        Ok(Self {
+            reader,
+            header,
+            verify_crc32: false,
+            verify_row_length: false,
+            stream_array: false,
+            invert: false,
+            bit_order: BitOrder::Lsb,
+            format,
+            leading_comment,
+            extra_defines,
+            offset,
         })
     }
 
+    /// Sets whether to verify the CRC-32 checksum of the packed bytes of the
+    /// image when decoding.
+    ///
+    /// The checksum is verified only if `#define <name>_crc32 <value>` is
+    /// defined. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Cursor;
+    /// #
+    /// # use xbm::{decode::Error, Decoder};
+    /// #
+    /// let image = "#define image_width 8
+    /// #define image_height 7
+    /// #define image_crc32 0x00000000
+    /// static unsigned char image_bits[] = {
+    ///     0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+    /// };
+    /// ";
+    ///
+    /// let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    /// assert!(decoder.decode_to_vec().is_ok());
+    ///
+    /// let decoder = Decoder::new(Cursor::new(image)).unwrap().verify_crc32(true);
+    /// assert!(matches!(
+    ///     decoder.decode_to_vec().unwrap_err(),
+    ///     Error::ChecksumMismatch { .. }
+    /// ));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn verify_crc32(mut self, verify: bool) -> Self {
+        self.verify_crc32 = verify;
+        self
+    }
+
+    /// Sets whether to verify that each line of the array contains exactly the
+    /// packed bytes of one row of the image when decoding.
+    ///
+    /// This is useful for the image in which each line of the array represents
+    /// a row, since an extra byte in one row and a missing byte in another row
+    /// cannot be detected otherwise. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Cursor;
+    /// #
+    /// # use xbm::{decode::Error, Decoder};
+    /// #
+    /// let image = "#define image_width 16
+    /// #define image_height 2
+    /// static unsigned char image_bits[] = {
+    ///     0x00, 0x1C, 0x24,
+    ///     0x1C,
+    /// };
+    /// ";
+    ///
+    /// let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    /// assert!(decoder.decode_to_vec().is_ok());
+    ///
+    /// let decoder = Decoder::new(Cursor::new(image))
+    ///     .unwrap()
+    ///     .verify_row_length(true);
+    /// assert!(matches!(
+    ///     decoder.decode_to_vec().unwrap_err(),
+    ///     Error::RowLengthMismatch {
+    ///         row: 0,
+    ///         expected: 2,
+    ///         found: 3
+    ///     }
+    /// ));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn verify_row_length(mut self, verify: bool) -> Self {
+        self.verify_row_length = verify;
+        self
+    }
+
+    /// Sets whether to read the array without buffering each line of it when
+    /// decoding.
+    ///
+    /// By default, each line of the array is read into memory before parsing,
+    /// so a minified image whose whole array is on one line is buffered
+    /// entirely. If this is `true`, the array is scanned incrementally, so the
+    /// memory usage does not depend on the length of the lines. Since the
+    /// lines are not tracked, [`Decoder::verify_row_length`] has no effect in
+    /// this mode. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic_single_line.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap().stream_array(true);
+    ///
+    /// let mut buf = [u8::default(); 56];
+    /// decoder.decode(&mut buf).unwrap();
+    /// assert_eq!(buf[8..16], [0, 0, 1, 1, 1, 0, 0, 0]);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn stream_array(mut self, stream: bool) -> Self {
+        self.stream_array = stream;
+        self
+    }
+
+    /// Sets whether to invert the polarity of the decoded pixels.
+    ///
+    /// If this is `true`, `1` represents a white pixel and `0` represents a
+    /// black pixel when decoding, and white pixels are decoded as `0` and black
+    /// pixels are decoded as `255` through `image::ImageDecoder`. This does
+    /// not affect the packed bytes. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// // "B" (8x7)
+    /// let expected = b"\x01\x01\x01\x01\x01\x01\x01\x01\
+    ///                  \x01\x01\x00\x00\x00\x01\x01\x01\
+    ///                  \x01\x01\x00\x01\x01\x00\x01\x01\
+    ///                  \x01\x01\x00\x00\x00\x01\x01\x01\
+    ///                  \x01\x01\x00\x01\x01\x00\x01\x01\
+    ///                  \x01\x01\x00\x00\x00\x01\x01\x01\
+    ///                  \x01\x01\x01\x01\x01\x01\x01\x01";
+    ///
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap().invert(true);
+    ///
+    /// let mut buf = [u8::default(); 56];
+    /// decoder.decode(&mut buf).unwrap();
+    /// assert_eq!(buf, *expected);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Sets the order of the pixels within each byte of the array.
+    ///
+    /// Use [`BitOrder::Msb`] to decode the images written by tools which put
+    /// the leftmost pixel in the most significant bit. The bytes are converted
+    /// to [`BitOrder::Lsb`] while decoding, so the packed bytes are always in
+    /// the order defined by XBM. The CRC-32 checksum is verified against the
+    /// bytes as written in the array. The default is [`BitOrder::Lsb`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Cursor;
+    /// #
+    /// # use xbm::{BitOrder, Decoder};
+    /// #
+    /// let image = "#define image_width 8
+    /// #define image_height 1
+    /// static unsigned char image_bits[] = {
+    ///     0x38,
+    /// };
+    /// ";
+    /// let decoder = Decoder::new(Cursor::new(image))
+    ///     .unwrap()
+    ///     .bit_order(BitOrder::Msb);
+    ///
+    /// let buf = decoder.decode_to_vec().unwrap();
+    /// assert_eq!(buf, [0, 0, 1, 1, 1, 0, 0, 0]);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn bit_order(mut self, bit_order: BitOrder) -> Self {
+        self.bit_order = bit_order;
+        self
+    }
+
     /// Returns the name of the image.
     ///
     /// # Examples
@@ -181,7 +603,7 @@ impl<R: BufRead + Seek> Decoder<R> {
     /// ```
     #[inline]
     pub fn name(&self) -> &str {
-        &self.name
+        &self.header.name
     }
 
     /// Returns the width of the image.
@@ -201,7 +623,7 @@ impl<R: BufRead + Seek> Decoder<R> {
     /// ```
     #[inline]
     pub const fn width(&self) -> u32 {
-        self.width
+        self.header.width
     }
 
     /// Returns the height of the image.
@@ -221,7 +643,77 @@ impl<R: BufRead + Seek> Decoder<R> {
     /// ```
     #[inline]
     pub const fn height(&self) -> u32 {
-        self.height
+        self.header.height
+    }
+
+    /// Returns the dimensions of the image as `(width, height)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert_eq!(decoder.dimensions(), (8, 7));
+    /// ```
+    #[inline]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.width(), self.height())
+    }
+
+    /// Returns the width of the image as [`NonZeroU32`].
+    ///
+    /// The width of the image is never `0` because the `Decoder` rejects it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert_eq!(decoder.nonzero_width().get(), 8);
+    /// ```
+    #[inline]
+    pub const fn nonzero_width(&self) -> NonZeroU32 {
+        match NonZeroU32::new(self.width()) {
+            Some(width) => width,
+            None => unreachable!(),
+        }
+    }
+
+    /// Returns the height of the image as [`NonZeroU32`].
+    ///
+    /// The height of the image is never `0` because the `Decoder` rejects it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert_eq!(decoder.nonzero_height().get(), 7);
+    /// ```
+    #[inline]
+    pub const fn nonzero_height(&self) -> NonZeroU32 {
+        match NonZeroU32::new(self.height()) {
+            Some(height) => height,
+            None => unreachable!(),
+        }
     }
 
     /// Returns the _x_ coordinate of the hotspot.
@@ -249,7 +741,7 @@ impl<R: BufRead + Seek> Decoder<R> {
     /// ```
     #[inline]
     pub const fn x_hot(&self) -> Option<u32> {
-        self.x_hot
+        self.header.x_hot
     }
 
     /// Returns the _y_ coordinate of the hotspot.
@@ -277,21 +769,276 @@ impl<R: BufRead + Seek> Decoder<R> {
     /// ```
     #[inline]
     pub const fn y_hot(&self) -> Option<u32> {
-        self.y_hot
+        self.header.y_hot
     }
 
-    /// Decodes the image into `buf`.
-    ///
-    /// `0` represents a white pixel and `1` represents a black pixel.
+    /// Returns the coordinates of the hotspot as `(x, y)`.
     ///
-    /// # Errors
+    /// Returns [`None`] if the hotspot is not defined.
     ///
-    /// Returns [`Err`] if any of the following are true:
+    /// # Examples
     ///
-    /// - The hex byte value is invalid.
-    /// - The image termination string is not `};`.
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert!(decoder.hotspot().is_none());
+    ///
+    /// let reader = File::open("tests/data/hotspot.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert_eq!(decoder.hotspot(), Some((4, 3)));
+    /// ```
+    #[inline]
+    pub const fn hotspot(&self) -> Option<(u32, u32)> {
+        self.header.hotspot()
+    }
+
+    /// Returns the CRC-32 checksum of the packed bytes of the image.
+    ///
+    /// Returns [`None`] if the value is not defined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert!(decoder.crc32().is_none());
+    /// ```
+    #[inline]
+    pub const fn crc32(&self) -> Option<u32> {
+        self.header.crc32
+    }
+
+    /// Returns the comment before the first `#define` directive.
+    ///
+    /// The comment is returned verbatim, including the comment delimiters, and
+    /// may span multiple lines. Returns [`None`] if there is no comment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Cursor;
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let image = "/* SPDX-License-Identifier: CC0-1.0 */
+    /// #define image_width 8
+    /// #define image_height 7
+    /// static unsigned char image_bits[] = {
+    ///     0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+    /// };
+    /// ";
+    ///
+    /// let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    /// assert_eq!(
+    ///     decoder.leading_comment(),
+    ///     Some("/* SPDX-License-Identifier: CC0-1.0 */")
+    /// );
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn leading_comment(&self) -> Option<&str> {
+        self.leading_comment.as_deref()
+    }
+
+    /// Returns the `#define` directives in the header which are not
+    /// recognized by the decoder, such as `#define image_depth 1`.
+    ///
+    /// Each element is a pair of the identifier and the value, in the order
+    /// they appear. The value is empty if it is omitted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Cursor;
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let image = "#define image_width 8
+    /// #define image_depth 1
+    /// #define image_height 7
+    /// static unsigned char image_bits[] = {
+    ///     0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+    /// };
+    /// ";
+    ///
+    /// let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    /// assert_eq!(
+    ///     decoder.extra_defines(),
+    ///     [(String::from("image_depth"), String::from("1"))]
+    /// );
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn extra_defines(&self) -> &[(String, String)] {
+        &self.extra_defines
+    }
+
+    /// Returns the header of the image.
+    ///
+    /// The returned [`Header`] does not borrow the reader, so it can outlive
+    /// the `Decoder`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/hotspot.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// let header = decoder.metadata();
+    /// drop(decoder);
+    /// assert_eq!(header.name(), "image");
+    /// assert_eq!(header.width(), 8);
+    /// assert_eq!(header.height(), 7);
+    /// assert_eq!(header.x_hot(), Some(4));
+    /// assert_eq!(header.y_hot(), Some(3));
+    /// ```
+    #[inline]
+    pub fn metadata(&self) -> Header {
+        self.header.clone()
+    }
+
+    /// Returns the number of pixels of the image.
+    ///
+    /// This is the length of the buffer required by [`Decoder::decode`].
+    /// Returns [`None`] if it does not fit in [`usize`] on the current
+    /// platform.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert_eq!(decoder.pixel_count(), Some(56));
+    /// ```
+    #[inline]
+    pub fn pixel_count(&self) -> Option<u64> {
+        self.required_buffer_len()
+            .and_then(|count| u64::try_from(count).ok())
+    }
+
+    /// Returns the length of the buffer required by [`Decoder::decode`].
+    ///
+    /// This is the width multiplied by the height, without any padding.
+    /// Returns [`None`] if it does not fit in [`usize`] on the current
+    /// platform, in which case the image cannot be decoded into memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/width_7.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// let len = decoder.required_buffer_len().unwrap();
+    /// assert_eq!(len, 42);
+    ///
+    /// let mut buf = vec![u8::default(); len];
+    /// decoder.decode(&mut buf).unwrap();
+    /// ```
+    #[inline]
+    pub fn required_buffer_len(&self) -> Option<usize> {
+        pixel_count(self.width(), self.height())
+    }
+
+    /// Returns the number of bits per pixel of the image.
+    ///
+    /// This is always `1` because XBM images are monochrome. Each pixel is
+    /// decoded into a byte, which is either `0` (white) or `1` (black).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert_eq!(decoder.bits_per_pixel(), 1);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn bits_per_pixel(&self) -> u8 {
+        1
+    }
+
+    /// Returns the format of the image.
+    ///
+    /// This is detected from the type of the array: [`Format::X11`] for
+    /// `char`, and [`Format::X10`] for `short`. Both are decoded into the same
+    /// pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::{decode::Format, Decoder};
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert_eq!(decoder.format(), Format::X11);
+    ///
+    /// let reader = File::open("tests/data/x10.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert_eq!(decoder.format(), Format::X10);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Decodes the image into `buf`.
+    ///
+    /// `0` represents a white pixel and `1` represents a black pixel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - The hex byte value is invalid.
+    /// - The image termination string is not `};`.
     /// - The expected image dimensions and the actual image dimensions
     ///   mismatch.
+    /// - The CRC-32 checksum mismatches when verifying it is enabled.
+    /// - The number of the packed bytes in a line of the array mismatches when
+    ///   verifying it is enabled.
     /// - An error occurs during I/O operations.
     /// - An error occurs while parsing the hex byte value.
     ///
@@ -325,141 +1072,2245 @@ impl<R: BufRead + Seek> Decoder<R> {
     /// decoder.decode(&mut buf).unwrap();
     /// assert_eq!(buf, *expected);
     /// ```
+    #[inline]
     pub fn decode(self, buf: &mut (impl AsMut<[u8]> + ?Sized)) -> Result<(), Error> {
-        let inner = |decoder: Self, buf: &mut [u8]| -> Result<(), Error> {
-            let buf_len = buf.len();
-            let width =
-                usize::try_from(decoder.width()).expect("width should be in the range of `usize`");
-            let dimensions = usize::try_from(decoder.height()).map(|h| width * h);
-            assert_eq!(
-                Ok(buf_len),
-                dimensions,
-                "`buf` and the image dimensions are different"
-            );
-
-            let mut pixels = [u8::default(); 8];
-            let mut remaining_pixels = width;
-            let mut pos = usize::default();
-
-            let mut lines_iter = decoder.reader.lines().peekable();
-            while let Some(line) = lines_iter.next() {
-                let line = line?;
-                let mut line = line.trim();
-
-                if lines_iter.peek().is_none() {
-                    if !line.ends_with("};") {
-                        return Err(Error::InvalidTermination);
-                    }
-                    line = line.trim_end_matches("};");
-                    if line.is_empty() {
-                        break;
-                    }
-                }
-
-                let mut line_iter = line
-                    .split_terminator(',')
-                    .map(str::trim)
-                    .map(String::from)
-                    .peekable();
-                while let Some(pixels_hex) = line_iter.next() {
-                    if line_iter.peek().is_none() && pixels_hex.is_empty() {
-                        break;
-                    }
+        let table = self.pixels_table();
+        self.decode_with_table(buf.as_mut(), table, false)
+    }
 
-                    if !pixels_hex.is_ascii()
-                        || pixels_hex.len() != 4
-                        || !pixels_hex.starts_with("0x")
-                    {
-                        return Err(Error::InvalidHexByte(pixels_hex));
-                    }
-                    let pixels_hex = pixels_hex.trim_start_matches("0x");
-                    let pixels_byte = u8::from_str_radix(pixels_hex, 16)?;
+    /// Decodes the image into `buf` of [`bool`]s.
+    ///
+    /// `true` represents a black pixel (a set bit) and `false` represents a
+    /// white pixel, which is the same as `1` and `0` of [`Decoder::decode`].
+    /// Note that this is the opposite of the grayscale image decoded through
+    /// `image::ImageDecoder`, where a black pixel is `0`. If
+    /// [`Decoder::invert`] is enabled, `true` represents a white pixel
+    /// instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs during decoding. See
+    /// [`Decoder::decode`] for the details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `buf` and the image dimensions (the width
+    /// multiplied by the height) are different.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let mut buf = [bool::default(); 56];
+    /// decoder.decode_bools(&mut buf).unwrap();
+    /// assert_eq!(
+    ///     buf[8..16],
+    ///     [false, false, true, true, true, false, false, false]
+    /// );
+    /// ```
+    pub fn decode_bools(self, buf: &mut [bool]) -> Result<(), Error> {
+        let table = if self.invert { &INVERTED_BOOLS } else { &BOOLS };
+        self.decode_with_table(buf, table, false)
+    }
 
-                    for (i, pixel) in pixels.iter_mut().enumerate() {
-                        *pixel = (pixels_byte >> i) & 1;
-                    }
+    /// Decodes the image into `buf`, ignoring any surplus bytes beyond the
+    /// image dimensions.
+    ///
+    /// This is the same as [`Decoder::decode`] except that the array is
+    /// allowed to contain more bytes than the image dimensions require.
+    ///
+    /// `0` represents a white pixel and `1` represents a black pixel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - The hex byte value is invalid.
+    /// - The image termination string is not `};`.
+    /// - The actual image dimensions are smaller than the expected image
+    ///   dimensions.
+    /// - The CRC-32 checksum mismatches when verifying it is enabled.
+    /// - The number of the packed bytes in a line of the array mismatches when
+    ///   verifying it is enabled.
+    /// - An error occurs during I/O operations.
+    /// - An error occurs while parsing the hex byte value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `buf` and the image dimensions (the width
+    /// multiplied by the height) are different.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Cursor;
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// // "B" (8x7)
+    /// let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let image = "#define image_width 8
+    /// #define image_height 7
+    /// static unsigned char image_bits[] = {
+    ///     0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0x00, 0x00,
+    /// };
+    /// ";
+    /// let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    ///
+    /// let mut buf = [u8::default(); 56];
+    /// decoder.decode_clamped(&mut buf).unwrap();
+    /// assert_eq!(buf, *expected);
+    /// ```
+    #[inline]
+    pub fn decode_clamped(self, buf: &mut (impl AsMut<[u8]> + ?Sized)) -> Result<(), Error> {
+        let table = self.pixels_table();
+        self.decode_with_table(buf.as_mut(), table, true)
+    }
 
-                    if remaining_pixels < 8 {
-                        buf[pos..(pos + remaining_pixels)]
-                            .copy_from_slice(&pixels[..remaining_pixels]);
-                        pos += remaining_pixels;
-                        remaining_pixels = width;
-                    } else {
-                        buf[pos..(pos + 8)].copy_from_slice(&pixels);
-                        pos += 8;
-                        remaining_pixels -= 8;
-                        if remaining_pixels == 0 {
-                            remaining_pixels = width;
-                        }
-                    }
-                }
-            }
+    #[allow(clippy::missing_panics_doc)]
+    /// Decodes the image into `buf` as far as it fits, and returns the number
+    /// of the written pixels.
+    ///
+    /// `0` represents a white pixel and `1` represents a black pixel.
+    ///
+    /// Unlike [`Decoder::decode`], `buf` may have any length. If `buf` is
+    /// shorter than the image, only the leading pixels are written. If `buf`
+    /// is longer than the image, or the array is shorter than the image
+    /// dimensions require, the rest of `buf` is left untouched. Surplus bytes
+    /// beyond the image dimensions are ignored. This is useful for decoding
+    /// into a fixed-size buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - The hex byte value is invalid.
+    /// - The image termination string is not `};`.
+    /// - The CRC-32 checksum mismatches when verifying it is enabled.
+    /// - The number of the packed bytes in a line of the array mismatches when
+    ///   verifying it is enabled.
+    /// - An error occurs during I/O operations.
+    /// - An error occurs while parsing the hex byte value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let mut buf = [u8::MAX; 24];
+    /// assert_eq!(decoder.decode_partial(&mut buf).unwrap(), 24);
+    /// assert_eq!(buf[8..16], [0, 0, 1, 1, 1, 0, 0, 0]);
+    ///
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let mut buf = [u8::MAX; 64];
+    /// assert_eq!(decoder.decode_partial(&mut buf).unwrap(), 56);
+    /// assert_eq!(buf[56..], [u8::MAX; 8]);
+    /// ```
+    pub fn decode_partial(self, buf: &mut [u8]) -> Result<usize, Error> {
+        let table = self.pixels_table();
+        let width = usize::try_from(self.width()).expect("width should be in the range of `usize`");
+        let limit =
+            pixel_count(self.width(), self.height()).map_or(buf.len(), |c| c.min(buf.len()));
+
+        let mut remaining_pixels = width;
+        let mut pos = usize::default();
+        self.read_bytes(|pixels_byte| {
+            let len = remaining_pixels.min(8);
+            if pos < limit {
+                let written = len.min(limit - pos);
+                buf[pos..pos + written]
+                    .copy_from_slice(&table[usize::from(pixels_byte)][..written]);
+            }
+            pos = pos.saturating_add(len);
+            remaining_pixels -= len;
+            if remaining_pixels == 0 {
+                remaining_pixels = width;
+            }
+        })?;
+        Ok(pos.min(limit))
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Decodes the image into `buf` in column-major order.
+    ///
+    /// The pixel at the _x_ coordinate `x` and the _y_ coordinate `y` is
+    /// written to `buf[x * height + y]`, that is, `buf` contains the columns of
+    /// the image from left to right, and each column contains the pixels from
+    /// top to bottom. This is the same as decoding the image with
+    /// [`Decoder::decode`] and then transposing it, so the resulting image is
+    /// `height` pixels wide and `width` pixels high.
+    ///
+    /// `0` represents a white pixel and `1` represents a black pixel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs during decoding. See
+    /// [`Decoder::decode`] for the details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `buf` and the image dimensions (the width
+    /// multiplied by the height) are different.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Cursor;
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let image = "#define image_width 3
+    /// #define image_height 2
+    /// static unsigned char image_bits[] = {
+    ///     0x05, 0x02,
+    /// };
+    /// ";
+    /// let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    ///
+    /// let mut buf = [u8::default(); 6];
+    /// decoder.decode_transposed(&mut buf).unwrap();
+    /// assert_eq!(buf, [1, 0, 0, 1, 1, 0]);
+    /// ```
+    pub fn decode_transposed(self, buf: &mut (impl AsMut<[u8]> + ?Sized)) -> Result<(), Error> {
+        let inner = |decoder: Self, buf: &mut [u8]| -> Result<(), Error> {
+            let buf_len = buf.len();
+            let width =
+                usize::try_from(decoder.width()).expect("width should be in the range of `usize`");
+            let height = usize::try_from(decoder.height())
+                .expect("height should be in the range of `usize`");
+            assert_eq!(
+                Some(buf_len),
+                width.checked_mul(height),
+                "`buf` and the image dimensions are different"
+            );
+
+            let table = decoder.pixels_table();
+            let (mut x, mut y) = (usize::default(), usize::default());
+            let mut pos = usize::default();
+
+            decoder.read_bytes(|pixels_byte| {
+                let pixels = &table[usize::from(pixels_byte)];
+
+                let len = (width - x).min(8);
+                if y < height {
+                    for (i, &pixel) in pixels[..len].iter().enumerate() {
+                        buf[(x + i) * height + y] = pixel;
+                    }
+                }
+                pos = pos.saturating_add(len);
+                x += len;
+                if x == width {
+                    x = usize::default();
+                    y += 1;
+                }
+            })?;
+
+            if pos == buf_len {
+                Ok(())
+            } else {
+                Err(invalid_image_size(width, height, pos))
+            }
+        };
+        inner(self, buf.as_mut())
+    }
+
+    /// Decodes the image into `buf` by expanding each byte using `table`.
+    ///
+    /// If `clamp` is `true`, any surplus bytes beyond the image dimensions are
+    /// ignored.
+    fn decode_with_table<T: Copy>(
+        self,
+        buf: &mut [T],
+        table: &[[T; 8]; 256],
+        clamp: bool,
+    ) -> Result<(), Error> {
+        let buf_len = buf.len();
+        let width = usize::try_from(self.width()).expect("width should be in the range of `usize`");
+        assert_eq!(
+            Some(buf_len),
+            pixel_count(self.width(), self.height()),
+            "`buf` and the image dimensions are different"
+        );
+
+        let mut remaining_pixels = width;
+        let mut pos = usize::default();
+
+        self.read_bytes(|pixels_byte| {
+            let pixels = &table[usize::from(pixels_byte)];
+
+            let len = remaining_pixels.min(8);
+            // Saturate so that surplus bytes never wrap the position around.
+            let end = pos.saturating_add(len);
+            if let Some(buf) = buf.get_mut(pos..end) {
+                buf.copy_from_slice(&pixels[..len]);
+            }
+            pos = end;
+            remaining_pixels -= len;
+            if remaining_pixels == 0 {
+                remaining_pixels = width;
+            }
+        })?;
+
+        if pos == buf_len || (clamp && pos > buf_len) {
+            Ok(())
+        } else {
+            Err(Error::InvalidImageSize {
+                expected_pixels: buf_len,
+                decoded_pixels: pos,
+            })
+        }
+    }
+
+    /// Returns the table which maps each byte to the pixels, taking the
+    /// polarity into account.
+    const fn pixels_table(&self) -> &'static [[u8; 8]; 256] {
+        if self.invert {
+            &INVERTED_PIXELS
+        } else {
+            &PIXELS
+        }
+    }
+
+    /// Returns the number of the packed bytes per row of the image.
+    fn bytes_per_line(&self) -> usize {
+        let width = usize::try_from(self.width()).expect("width should be in the range of `usize`");
+        bytes_per_line(width)
+    }
+
+    /// Returns the number of the elements of the array per row of the image.
+    fn elements_per_line(&self) -> usize {
+        let bytes_per_line = self.bytes_per_line();
+        match self.format {
+            Format::X10 => bytes_per_line / 2 + bytes_per_line % 2,
+            Format::X11 => bytes_per_line,
+        }
+    }
+
+    /// Reads the hex byte values of the image and calls `f` with each of them
+    /// in order.
+    ///
+    /// The elements of the X10 format are split into bytes, and the padding
+    /// byte at the end of each row is skipped, so `f` is always called with
+    /// the packed bytes in the X11 format.
+    fn read_bytes(self, f: impl FnMut(u8)) -> Result<(), Error> {
+        if self.stream_array {
+            self.read_array_streaming(f)
+        } else {
+            self.read_array(false, f).map(|_| ())
+        }
+    }
+
+    /// Reads the hex byte values of the image and calls `f` with each of them
+    /// in order, and returns the reader.
+    ///
+    /// If `stops_at_termination` is `true`, this stops at the first line which
+    /// ends with `};`, leaving the reader positioned after it. Otherwise, the
+    /// line which ends with `};` must be the last line.
+    fn read_array(self, stops_at_termination: bool, mut f: impl FnMut(u8)) -> Result<R, Error> {
+        let mut elements = self.element_parser();
+        let reader = self.read_tokens(stops_at_termination, |token, offset| {
+            elements.push(token, offset, &mut f)
+        })?;
+        elements.finish()?;
+        Ok(reader)
+    }
+
+    /// Reads the array line by line and calls `f` with each of the tokens of
+    /// the elements and its byte offset in the reader, and returns the reader.
+    ///
+    /// See [`Decoder::read_array`] for `stops_at_termination`. The structure
+    /// of the array, such as the image termination string and the number of
+    /// the elements in a line, is verified here, but the tokens are not
+    /// parsed.
+    fn read_tokens(
+        self,
+        stops_at_termination: bool,
+        mut f: impl FnMut(&str, usize) -> Result<(), Error>,
+    ) -> Result<R, Error> {
+        let row_length = self.verify_row_length.then_some(self.elements_per_line());
+
+        let mut reader = self.reader;
+        let mut offset = self.offset;
+        let mut line = String::new();
+        let mut next_line = String::new();
+        let mut has_line = read_non_blank_line(&mut reader, &mut line, &mut offset)?;
+        if !has_line {
+            return Err(Error::UnexpectedEof);
+        }
+        let mut line_offset = offset - line.len();
+        let mut row = usize::default();
+        let mut has_brace = false;
+        while has_line {
+            let code = strip_comments(&line);
+            let mut trimmed = code.trim_end();
+            has_brace |= trimmed.contains('}');
+            let is_last = if stops_at_termination && trimmed.ends_with("};") {
+                true
+            } else {
+                has_line = read_non_blank_line(&mut reader, &mut next_line, &mut offset)?;
+                !has_line
+            };
+
+            if is_last {
+                if !has_brace {
+                    return Err(Error::UnexpectedEof);
+                }
+                if !trimmed.ends_with("};") {
+                    return Err(Error::InvalidTermination);
+                }
+                trimmed = trimmed.trim_end_matches("};");
+                if trimmed.trim_start().is_empty() {
+                    break;
+                }
+            }
+
+            let mut found = usize::default();
+            for (pos, pixels_hex) in split_line(trimmed) {
+                f(pixels_hex, line_offset + pos)?;
+                found += 1;
+            }
+            if let Some(expected) = row_length.filter(|&e| e != found) {
+                return Err(Error::RowLengthMismatch {
+                    row,
+                    expected,
+                    found,
+                });
+            }
+            row += 1;
+            if is_last {
+                break;
+            }
+
+            mem::swap(&mut line, &mut next_line);
+            line_offset = offset - line.len();
+        }
+        Ok(reader)
+    }
+
+    /// Reads the hex byte values of the image without buffering each line of
+    /// the array, and calls `f` with each of them in order.
+    ///
+    /// The array is scanned byte by byte, so the memory usage does not depend
+    /// on the length of the lines. Text after `};` must be whitespace or
+    /// comments.
+    #[allow(clippy::too_many_lines)]
+    fn read_array_streaming(self, mut f: impl FnMut(u8)) -> Result<(), Error> {
+        /// The maximum length of a token, which is long enough for any valid
+        /// element.
+        const MAX_TOKEN_LEN: usize = 16;
+
+        #[derive(Clone, Copy, Eq, PartialEq)]
+        enum Phase {
+            Body,
+            AfterBrace,
+            AfterSemicolon,
+        }
+
+        let mut elements = self.element_parser();
+        let mut reader = self.reader;
+        let mut offset = self.offset;
+        let mut phase = Phase::Body;
+        let mut token = Vec::with_capacity(MAX_TOKEN_LEN);
+        let mut token_offset = offset;
+        let mut has_space = false;
+        let mut empty_token = None;
+        let mut in_block_comment = false;
+        let mut in_line_comment = false;
+        let mut prev = u8::default();
+        let mut pending_slash = false;
+
+        // Processes a byte outside comments.
+        let mut process = |byte: u8, pos: usize, phase: &mut Phase| -> Result<(), Error> {
+            // As with the lines of the array, an empty token is allowed only
+            // at the end of a line.
+            let mut finish_token = |token: &mut Vec<u8>, is_comma: bool| {
+                let value = String::from_utf8_lossy(token);
+                let trimmed = value.trim();
+                if trimmed.is_empty() {
+                    if is_comma {
+                        if let Some(offset) = empty_token.replace(pos) {
+                            return Err(Error::InvalidHexByte {
+                                value: String::new(),
+                                offset,
+                            });
+                        }
+                    } else {
+                        empty_token = None;
+                    }
+                } else {
+                    if let Some(offset) = empty_token.take() {
+                        return Err(Error::InvalidHexByte {
+                            value: String::new(),
+                            offset,
+                        });
+                    }
+                    let leading = value.len() - value.trim_start().len();
+                    elements.push(trimmed, token_offset + leading, &mut f)?;
+                }
+                token.clear();
+                Ok(())
+            };
+            match *phase {
+                Phase::Body => match byte {
+                    b',' => {
+                        finish_token(&mut token, true)?;
+                        has_space = false;
+                    }
+                    b'}' => {
+                        finish_token(&mut token, false)?;
+                        *phase = Phase::AfterBrace;
+                    }
+                    b'\n' => {
+                        finish_token(&mut token, false)?;
+                        has_space = false;
+                    }
+                    b if is_space(b) => has_space = !token.is_empty(),
+                    b => {
+                        if token.is_empty() {
+                            token_offset = pos;
+                        } else if has_space {
+                            token.push(b' ');
+                            has_space = false;
+                        }
+                        token.push(b);
+                        if token.len() > MAX_TOKEN_LEN {
+                            return Err(Error::InvalidHexByte {
+                                value: String::from_utf8_lossy(&token).into_owned(),
+                                offset: token_offset,
+                            });
+                        }
+                    }
+                },
+                Phase::AfterBrace => match byte {
+                    b';' => *phase = Phase::AfterSemicolon,
+                    b if is_space(b) => {}
+                    _ => return Err(Error::InvalidTermination),
+                },
+                Phase::AfterSemicolon => {
+                    if !is_space(byte) {
+                        return Err(Error::InvalidTermination);
+                    }
+                }
+            }
+            Ok(())
+        };
+
+        loop {
+            let buf = reader.fill_buf()?;
+            if buf.is_empty() {
+                break;
+            }
+            for (i, &byte) in buf.iter().enumerate() {
+                let pos = offset + i;
+                if in_block_comment {
+                    // As with the lines of the array, an unterminated comment
+                    // ends at the end of the line.
+                    if byte == b'\n' {
+                        in_block_comment = false;
+                        process(byte, pos, &mut phase)?;
+                    } else if prev == b'*' && byte == b'/' {
+                        in_block_comment = false;
+                        process(b' ', pos, &mut phase)?;
+                    }
+                    prev = byte;
+                    continue;
+                }
+                if in_line_comment {
+                    if byte == b'\n' {
+                        in_line_comment = false;
+                        process(byte, pos, &mut phase)?;
+                    }
+                    continue;
+                }
+                if pending_slash {
+                    pending_slash = false;
+                    match byte {
+                        b'*' => {
+                            in_block_comment = true;
+                            // Reset so that `/*/` does not end the comment.
+                            prev = u8::default();
+                            continue;
+                        }
+                        b'/' => {
+                            in_line_comment = true;
+                            continue;
+                        }
+                        _ => process(b'/', pos - 1, &mut phase)?,
+                    }
+                }
+                if byte == b'/' {
+                    pending_slash = true;
+                } else {
+                    process(byte, pos, &mut phase)?;
+                }
+            }
+            let len = buf.len();
+            offset += len;
+            reader.consume(len);
+        }
+        if pending_slash {
+            process(b'/', offset - 1, &mut phase)?;
+        }
+        match phase {
+            Phase::Body => return Err(Error::UnexpectedEof),
+            Phase::AfterBrace => return Err(Error::InvalidTermination),
+            Phase::AfterSemicolon => {}
+        }
+        elements.finish()
+    }
+
+    /// Returns the state for converting the elements of the array into the
+    /// packed bytes.
+    fn element_parser(&self) -> ElementParser {
+        ElementParser {
+            format: self.format,
+            bytes_per_line: self.bytes_per_line(),
+            elements_per_line: self.elements_per_line(),
+            bit_order: self.bit_order,
+            column: usize::default(),
+            checksum: self
+                .header
+                .crc32
+                .filter(|_| self.verify_crc32)
+                .map(|expected| (expected, Crc32::new())),
+        }
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Decodes the image into a newly allocated [`Vec`].
+    ///
+    /// `0` represents a white pixel and `1` represents a black pixel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - The hex byte value is invalid.
+    /// - The image termination string is not `};`.
+    /// - The expected image dimensions and the actual image dimensions
+    ///   mismatch.
+    /// - The CRC-32 checksum mismatches when verifying it is enabled.
+    /// - The number of the packed bytes in a line of the array mismatches when
+    ///   verifying it is enabled.
+    /// - An error occurs during I/O operations.
+    /// - An error occurs while parsing the hex byte value.
+    /// - The number of pixels of the image exceeds the range of [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// // "B" (8x7)
+    /// let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let buf = decoder.decode_to_vec().unwrap();
+    /// assert_eq!(buf, expected);
+    /// ```
+    #[inline]
+    pub fn decode_to_vec(self) -> Result<Vec<u8>, Error> {
+        let mut buf = Vec::new();
+        self.decode_into_vec(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Decodes the image into `buf`, resizing it to the image dimensions.
+    ///
+    /// `0` represents a white pixel and `1` represents a black pixel.
+    ///
+    /// `buf` is cleared before decoding, and its allocation is reused if its
+    /// capacity is large enough. This is useful for decoding many images of
+    /// varying dimensions with a single buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs during decoding. See
+    /// [`Decoder::decode_to_vec`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let mut buf = Vec::new();
+    ///
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// decoder.decode_into_vec(&mut buf).unwrap();
+    /// assert_eq!(buf.len(), 56);
+    ///
+    /// let reader = File::open("tests/data/width_7.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// decoder.decode_into_vec(&mut buf).unwrap();
+    /// assert_eq!(buf.len(), 42);
+    /// ```
+    #[inline]
+    pub fn decode_into_vec(self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        let dimensions = self.required_buffer_len().ok_or(Error::ImageTooLarge)?;
+        buf.clear();
+        buf.resize(dimensions, u8::default());
+        self.decode(buf)
+    }
+
+    #[cfg(feature = "image")]
+    #[allow(clippy::missing_panics_doc)]
+    /// Decodes the image into a [`GrayImage`](image::GrayImage).
+    ///
+    /// White pixels are decoded as `255` and black pixels are decoded as `0`.
+    ///
+    /// Since [`ImageBuffer`](image::ImageBuffer) requires a container which
+    /// dereferences to the pixel values, the pixels cannot be computed lazily
+    /// from the packed bytes. Instead, this allocates the buffer for the image
+    /// only once and expands the hex byte values into it directly, without any
+    /// intermediate buffers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs during decoding. See
+    /// [`Decoder::decode`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/qr_code.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let image = decoder.into_gray_image().unwrap();
+    /// let expected = image::open("tests/data/qr_code.png").unwrap().into_luma8();
+    /// assert_eq!(image, expected);
+    /// ```
+    pub fn into_gray_image(self) -> image::ImageResult<image::GrayImage> {
+        use image::{
+            error::{LimitError, LimitErrorKind},
+            GrayImage, ImageDecoder, ImageError,
+        };
+
+        let (width, height) = (self.width(), self.height());
+        let dimensions = pixel_count(width, height).ok_or_else(|| {
+            ImageError::Limits(LimitError::from_kind(LimitErrorKind::InsufficientMemory))
+        })?;
+        let mut buf = vec![u8::default(); dimensions];
+        self.read_image(&mut buf)?;
+        Ok(GrayImage::from_raw(width, height, buf)
+            .expect("buffer should be large enough for the image dimensions"))
+    }
+
+    #[cfg(feature = "image")]
+    #[allow(clippy::missing_panics_doc)]
+    /// Decodes the image into an [`RgbaImage`](image::RgbaImage).
+    ///
+    /// White pixels are decoded as `[255, 255, 255, 255]` and black pixels are
+    /// decoded as `[0, 0, 0, 255]`. If [`Decoder::invert`] is enabled, they are
+    /// swapped.
+    ///
+    /// This decodes the image into the buffer for the image as
+    /// [`ColorType::L8`](image::ColorType::L8) first, and then expands each
+    /// pixel into four bytes in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs during decoding. See
+    /// [`Decoder::decode`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use image::Rgba;
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let image = decoder.decode_rgba().unwrap();
+    /// assert_eq!(image.dimensions(), (8, 7));
+    /// assert_eq!(image.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+    /// assert_eq!(image.get_pixel(2, 1), &Rgba([0, 0, 0, 255]));
+    /// ```
+    pub fn decode_rgba(self) -> image::ImageResult<image::RgbaImage> {
+        use image::{
+            error::{LimitError, LimitErrorKind},
+            ImageDecoder, ImageError, RgbaImage,
+        };
+
+        let (width, height) = (self.width(), self.height());
+        let dimensions = pixel_count(width, height).ok_or_else(|| {
+            ImageError::Limits(LimitError::from_kind(LimitErrorKind::InsufficientMemory))
+        })?;
+        let len = dimensions.checked_mul(4).ok_or_else(|| {
+            ImageError::Limits(LimitError::from_kind(LimitErrorKind::InsufficientMemory))
+        })?;
+        let mut buf = vec![u8::default(); len];
+        self.read_image(&mut buf[..dimensions])?;
+        // Iterate backward so that each luma value is read before it is
+        // overwritten.
+        for i in (0..dimensions).rev() {
+            let luma = buf[i];
+            buf[(i * 4)..(i * 4 + 4)].copy_from_slice(&[luma, luma, luma, u8::MAX]);
+        }
+        Ok(RgbaImage::from_raw(width, height, buf)
+            .expect("buffer should be large enough for the image dimensions"))
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Decodes the image into the packed bytes.
+    ///
+    /// The returned bytes are the hex byte values of the array as they are.
+    /// Each line of the image is padded to a multiple of 8 pixels, and the
+    /// least significant bit of each byte is the leftmost pixel.
+    ///
+    /// The bytes are always newly allocated. They are parsed from the hex byte
+    /// values in the text of the image, so they cannot be borrowed from the
+    /// input even if the whole image is in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - The hex byte value is invalid.
+    /// - The image termination string is not `};`.
+    /// - The expected image dimensions and the actual image dimensions
+    ///   mismatch.
+    /// - The CRC-32 checksum mismatches when verifying it is enabled.
+    /// - The number of the packed bytes in a line of the array mismatches when
+    ///   verifying it is enabled.
+    /// - An error occurs during I/O operations.
+    /// - An error occurs while parsing the hex byte value.
+    /// - The number of the packed bytes of the image exceeds the range of
+    ///   [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let buf = decoder.decode_packed().unwrap();
+    /// assert_eq!(buf, b"\x00\x1C\x24\x1C\x24\x1C\x00");
+    /// ```
+    #[inline]
+    pub fn decode_packed(self) -> Result<Vec<u8>, Error> {
+        let mut buf = Vec::new();
+        self.decode_packed_into(&mut buf)?;
+        Ok(buf)
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Decodes the image into `buf` as the packed bytes.
+    ///
+    /// This is the same as [`Decoder::decode_packed`], except that `buf`
+    /// is cleared before decoding and its allocation is reused if its capacity
+    /// is large enough. This is useful for decoding many small images in a
+    /// loop with a single buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs during decoding. See
+    /// [`Decoder::decode_packed`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let mut buf = Vec::new();
+    ///
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// decoder.decode_packed_into(&mut buf).unwrap();
+    /// assert_eq!(buf, b"\x00\x1C\x24\x1C\x24\x1C\x00");
+    ///
+    /// let reader = File::open("tests/data/16x14.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// decoder.decode_packed_into(&mut buf).unwrap();
+    /// assert_eq!(buf.len(), 28);
+    /// ```
+    pub fn decode_packed_into(self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        let width = usize::try_from(self.width()).expect("width should be in the range of `usize`");
+        let height =
+            usize::try_from(self.height()).expect("height should be in the range of `usize`");
+        let len = bytes_per_line(width)
+            .checked_mul(height)
+            .ok_or(Error::ImageTooLarge)?;
+
+        buf.clear();
+        buf.reserve(len);
+        self.read_bytes(|byte| buf.push(byte))?;
+        if buf.len() == len {
+            Ok(())
+        } else {
+            Err(invalid_image_size(
+                width,
+                height,
+                packed_pixels(buf.len(), width),
+            ))
+        }
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Decodes the image into both the packed bytes and the pixels in a single
+    /// pass.
+    ///
+    /// The first element of the returned tuple is the packed bytes, which are
+    /// the hex byte values of the array as they are. Each line of the image is
+    /// padded to a multiple of 8 pixels, and the least significant bit of each
+    /// byte is the leftmost pixel. This is the same as
+    /// [`Decoder::decode_packed`].
+    ///
+    /// The second element is the pixels in row-major order, where `0`
+    /// represents a white pixel and `1` represents a black pixel. This is the
+    /// same as [`Decoder::decode_to_vec`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs during decoding. See
+    /// [`Decoder::decode`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// // "B" (8x7)
+    /// let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let (packed, pixels) = decoder.decode_both().unwrap();
+    /// assert_eq!(packed, b"\x00\x1C\x24\x1C\x24\x1C\x00");
+    /// assert_eq!(pixels, expected);
+    /// ```
+    pub fn decode_both(self) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let width = usize::try_from(self.width()).expect("width should be in the range of `usize`");
+        let height =
+            usize::try_from(self.height()).expect("height should be in the range of `usize`");
+        let len = bytes_per_line(width)
+            .checked_mul(height)
+            .ok_or(Error::ImageTooLarge)?;
+        let table = self.pixels_table();
+
+        let mut packed = Vec::with_capacity(len);
+        let mut pixels =
+            Vec::with_capacity(self.required_buffer_len().ok_or(Error::ImageTooLarge)?);
+        let mut remaining_pixels = width;
+        self.read_bytes(|byte| {
+            packed.push(byte);
+            let len = remaining_pixels.min(8);
+            pixels.extend_from_slice(&table[usize::from(byte)][..len]);
+            remaining_pixels -= len;
+            if remaining_pixels == 0 {
+                remaining_pixels = width;
+            }
+        })?;
+        if packed.len() == len {
+            Ok((packed, pixels))
+        } else {
+            Err(invalid_image_size(
+                width,
+                height,
+                packed_pixels(packed.len(), width),
+            ))
+        }
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[allow(clippy::missing_panics_doc)]
+    /// Decodes the image into a [`BitVec`](bitvec::vec::BitVec) with one bit
+    /// per pixel.
+    ///
+    /// The pixels are stored in row-major order, where `0` represents a white
+    /// pixel and `1` represents a black pixel. Unlike the packed bytes, the
+    /// padding bits at the end of each row are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs during decoding. See
+    /// [`Decoder::decode_to_vec`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/width_7.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let bits = decoder.decode_to_bitvec().unwrap();
+    /// assert_eq!(bits.len(), 42);
+    /// assert_eq!(bits.count_ones(), 8);
+    /// ```
+    pub fn decode_to_bitvec(self) -> Result<bitvec::vec::BitVec<u8, bitvec::order::Lsb0>, Error> {
+        use bitvec::{order::Lsb0, vec::BitVec, view::BitView};
+
+        let dimensions = self.required_buffer_len().ok_or(Error::ImageTooLarge)?;
+        let width = usize::try_from(self.width()).expect("width should be in the range of `usize`");
+        let mask = if self.invert { u8::MAX } else { u8::MIN };
+
+        let mut bits = BitVec::<u8, Lsb0>::with_capacity(dimensions);
+        let mut remaining_pixels = width;
+        let mut pos = usize::default();
+        self.read_bytes(|byte| {
+            let len = remaining_pixels.min(8);
+            let end = pos.saturating_add(len);
+            if end <= dimensions {
+                bits.extend_from_bitslice(&(byte ^ mask).view_bits::<Lsb0>()[..len]);
+            }
+            pos = end;
+            remaining_pixels -= len;
+            if remaining_pixels == 0 {
+                remaining_pixels = width;
+            }
+        })?;
+        if pos == dimensions {
+            Ok(bits)
+        } else {
+            Err(Error::InvalidImageSize {
+                expected_pixels: dimensions,
+                decoded_pixels: pos,
+            })
+        }
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Returns an iterator over the coordinates of the black pixels of the
+    /// image.
+    ///
+    /// The iterator yields the `(x, y)` coordinate of each pixel whose value is
+    /// `1` in row-major order. The padding bits at the end of each row are
+    /// skipped. This reads only the packed bytes into memory instead of the
+    /// pixels, so this is useful for sparse images.
+    ///
+    /// If an error occurs during decoding, the iterator yields only the error.
+    /// See [`Decoder::decode`] for the details of the errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let pixels = decoder.set_pixels().collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(pixels.len(), 13);
+    /// assert_eq!(pixels[..4], [(2, 1), (3, 1), (4, 1), (2, 2)]);
+    /// ```
+    pub fn set_pixels(self) -> impl Iterator<Item = Result<(u32, u32), Error>> {
+        let width = self.width();
+        let bytes_per_line = self.bytes_per_line();
+        let mask = if self.invert { u8::MAX } else { u8::MIN };
+        let (packed, err) = match self.decode_packed() {
+            Ok(packed) => (packed, None),
+            Err(err) => (Vec::new(), Some(err)),
+        };
+        err.map(Err).into_iter().chain(
+            packed
+                .into_iter()
+                .enumerate()
+                .flat_map(move |(i, byte)| {
+                    let byte = byte ^ mask;
+                    let y = u32::try_from(i / bytes_per_line)
+                        .expect("y coordinate should be in the range of `u32`");
+                    let x = u32::try_from(i % bytes_per_line * 8)
+                        .expect("x coordinate should be in the range of `u32`");
+                    (0..8)
+                        .filter(move |bit| (byte >> bit) & 1 == 1)
+                        .map(move |bit| (x + bit, y))
+                })
+                .filter(move |&(x, _)| x < width)
+                .map(Ok),
+        )
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Validates the image without decoding it into pixels.
+    ///
+    /// This reads the whole array, and checks the hex byte values, the number
+    /// of the packed bytes, and the image termination string in the same way
+    /// as [`Decoder::decode`], but does not allocate a buffer for the pixels.
+    /// This is useful as a cheap integrity check before decoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs during decoding. See
+    /// [`Decoder::decode`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Cursor;
+    /// #
+    /// # use xbm::{decode::Error, Decoder};
+    /// #
+    /// let decoder = Decoder::from_bytes(include_bytes!("../tests/data/basic.xbm")).unwrap();
+    /// assert!(decoder.validate().is_ok());
+    ///
+    /// let image = "#define image_width 8
+    /// #define image_height 7
+    /// static unsigned char image_bits[] = {
+    ///     0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
+    /// };
+    /// ";
+    /// let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    /// assert!(matches!(
+    ///     decoder.validate().unwrap_err(),
+    ///     Error::InvalidImageSize {
+    ///         expected_pixels: 56,
+    ///         decoded_pixels: 48
+    ///     }
+    /// ));
+    /// ```
+    pub fn validate(self) -> Result<(), Error> {
+        let width = usize::try_from(self.width()).expect("width should be in the range of `usize`");
+        let height =
+            usize::try_from(self.height()).expect("height should be in the range of `usize`");
+        let len = self
+            .bytes_per_line()
+            .checked_mul(height)
+            .ok_or(Error::ImageTooLarge)?;
+        let mut count = usize::default();
+        self.read_bytes(|_| count += 1)?;
+        if count == len {
+            Ok(())
+        } else {
+            Err(invalid_image_size(
+                width,
+                height,
+                packed_pixels(count, width),
+            ))
+        }
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Skips the image without decoding it, and returns the reader positioned
+    /// after the image.
+    ///
+    /// This reads the array through the first line which ends with `};` and
+    /// validates the hex byte values, but does not expand them into pixels.
+    /// This is useful for reaching the next image in a file which contains
+    /// multiple images.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - The hex byte value is invalid.
+    /// - The array is not terminated by `};`.
+    /// - The expected image dimensions and the actual image dimensions
+    ///   mismatch.
+    /// - The CRC-32 checksum mismatches when verifying it is enabled.
+    /// - The number of the packed bytes in a line of the array mismatches when
+    ///   verifying it is enabled.
+    /// - An error occurs during I/O operations.
+    /// - An error occurs while parsing the hex byte value.
+    /// - The number of the packed bytes of the image exceeds the range of
+    ///   [`usize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/frames.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert_eq!(decoder.name(), "frame0");
+    ///
+    /// let reader = decoder.skip_image().unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    /// assert_eq!(decoder.name(), "frame1");
+    /// ```
+    pub fn skip_image(self) -> Result<R, Error> {
+        let width = usize::try_from(self.width()).expect("width should be in the range of `usize`");
+        let height =
+            usize::try_from(self.height()).expect("height should be in the range of `usize`");
+        let len = self
+            .bytes_per_line()
+            .checked_mul(height)
+            .ok_or(Error::ImageTooLarge)?;
+        let mut count = usize::default();
+        let reader = self.read_array(true, |_| count += 1)?;
+        if count == len {
+            Ok(reader)
+        } else {
+            Err(invalid_image_size(
+                width,
+                height,
+                packed_pixels(count, width),
+            ))
+        }
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Returns an iterator over the rows of the image.
+    ///
+    /// Each row is a [`Vec`] of the pixels, where `0` represents a white pixel
+    /// and `1` represents a black pixel. The image is decoded when this is
+    /// called, and the iterator knows the number of the remaining rows.
+    ///
+    /// If an error occurs during decoding, the iterator yields only the error.
+    /// See [`Decoder::decode`] for the details of the errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let mut rows = decoder.rows();
+    /// assert_eq!(rows.len(), 7);
+    /// assert_eq!(rows.next().unwrap().unwrap(), [0; 8]);
+    /// assert_eq!(rows.next().unwrap().unwrap(), [0, 0, 1, 1, 1, 0, 0, 0]);
+    /// assert_eq!(rows.len(), 5);
+    /// ```
+    pub fn rows(self) -> Rows {
+        let width = usize::try_from(self.width()).expect("width should be in the range of `usize`");
+        match self.decode_to_vec() {
+            Ok(pixels) => Rows {
+                pixels,
+                width,
+                row: usize::default(),
+                err: None,
+            },
+            Err(err) => Rows {
+                pixels: Vec::new(),
+                width,
+                row: usize::default(),
+                err: Some(err),
+            },
+        }
+    }
+
+    /// Decodes the image into the rows of the pixels.
+    ///
+    /// Returns `height` [`Vec`]s, each of which contains `width` pixels, so
+    /// that a pixel can be indexed by `[y][x]`. `0` represents a white pixel
+    /// and `1` represents a black pixel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs during decoding. See
+    /// [`Decoder::decode_to_vec`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let rows = decoder.decode_to_rows().unwrap();
+    /// assert_eq!(rows.len(), 7);
+    /// assert_eq!(rows[1], [0, 0, 1, 1, 1, 0, 0, 0]);
+    /// assert_eq!(rows[2][5], 1);
+    /// ```
+    #[inline]
+    pub fn decode_to_rows(self) -> Result<Vec<Vec<u8>>, Error> {
+        self.rows().collect()
+    }
+
+    #[cfg(feature = "rayon")]
+    /// Decodes the image into `buf` in parallel.
+    ///
+    /// This splits the array into the hex byte values line by line in the same
+    /// way as [`Decoder::decode`], then parses them and expands them into
+    /// pixels using [`rayon`]. The result, including the error, is identical
+    /// to [`Decoder::decode`]. The images in the X10 format and the images
+    /// read with [`Decoder::stream_array`] are decoded with
+    /// [`Decoder::decode`].
+    ///
+    /// `0` represents a white pixel and `1` represents a black pixel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - The hex byte value is invalid.
+    /// - The image termination string is not `};`.
+    /// - The expected image dimensions and the actual image dimensions
+    ///   mismatch.
+    /// - The CRC-32 checksum mismatches when verifying it is enabled.
+    /// - The number of the packed bytes in a line of the array mismatches when
+    ///   verifying it is enabled.
+    /// - An error occurs during I/O operations.
+    /// - An error occurs while parsing the hex byte value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `buf` and the image dimensions (the width
+    /// multiplied by the height) are different.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// // "B" (8x7)
+    /// let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                  \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let reader = File::open("tests/data/basic.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let decoder = Decoder::new(reader).unwrap();
+    ///
+    /// let mut buf = [u8::default(); 56];
+    /// decoder.decode_parallel(&mut buf).unwrap();
+    /// assert_eq!(buf, *expected);
+    /// ```
+    pub fn decode_parallel(self, buf: &mut (impl AsMut<[u8]> + ?Sized)) -> Result<(), Error> {
+        use rayon::{
+            iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator},
+            slice::{ParallelSlice, ParallelSliceMut},
+        };
+
+        if self.format == Format::X10 || self.stream_array {
+            return self.decode(buf);
+        }
+
+        let inner = |decoder: Self, buf: &mut [u8]| -> Result<(), Error> {
+            let buf_len = buf.len();
+            let table = decoder.pixels_table();
+            let width =
+                usize::try_from(decoder.width()).expect("width should be in the range of `usize`");
+            let height = usize::try_from(decoder.height())
+                .expect("height should be in the range of `usize`");
+            assert_eq!(
+                Some(buf_len),
+                width.checked_mul(height),
+                "`buf` and the image dimensions are different"
+            );
+
+            // Collect the tokens sequentially, since a newline ends a token. No
+            // token is collected after an error in the structure of the array,
+            // so an invalid token is always found before it, as `decode` does.
+            let (crc32, bit_order) = (
+                decoder.header.crc32.filter(|_| decoder.verify_crc32),
+                decoder.bit_order,
+            );
+            let mut text = String::new();
+            let mut tokens = Vec::new();
+            let structure_err = decoder
+                .read_tokens(false, |token, offset| {
+                    tokens.push((text.len(), token.len(), offset));
+                    text.push_str(token);
+                    Ok(())
+                })
+                .err();
+
+            let parse = |&(start, len, offset): &(usize, usize, usize)| {
+                parse_hex_byte(&text[start..start + len], offset)
+            };
+            let bytes = match tokens.par_iter().map(parse).collect::<Result<Vec<_>, _>>() {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    // Report the first error in the array, as `decode` does.
+                    return Err(tokens.iter().find_map(|t| parse(t).err()).unwrap_or(err));
+                }
+            };
+            if let Some(err) = structure_err {
+                return Err(err);
+            }
+            if let Some(expected) = crc32 {
+                let actual = Crc32::checksum(bytes.iter().copied());
+                if actual != expected {
+                    return Err(Error::ChecksumMismatch { expected, actual });
+                }
+            }
+
+            let bytes_per_line = bytes_per_line(width);
+            if bytes.len() != bytes_per_line * height {
+                return Err(invalid_image_size(
+                    width,
+                    height,
+                    packed_pixels(bytes.len(), width),
+                ));
+            }
+            buf.par_chunks_mut(width)
+                .zip(bytes.par_chunks(bytes_per_line))
+                .for_each(|(line, bytes)| {
+                    for (pixels, &byte) in line.chunks_mut(8).zip(bytes) {
+                        let byte = bit_order.convert(byte);
+                        pixels.copy_from_slice(&table[usize::from(byte)][..pixels.len()]);
+                    }
+                });
+            Ok(())
+        };
+        inner(self, buf.as_mut())
+    }
+}
+
+impl<'a> Decoder<Cursor<&'a [u8]>> {
+    /// Creates a new `Decoder` from the byte slice `bytes`.
+    ///
+    /// This is a shorthand for [`Decoder::new`] with `bytes` wrapped in a
+    /// [`Cursor`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs while parsing the header. See
+    /// [`Decoder::new`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Decoder;
+    /// #
+    /// let decoder = Decoder::from_bytes(include_bytes!("../tests/data/basic.xbm")).unwrap();
+    /// assert_eq!(decoder.width(), 8);
+    /// assert_eq!(decoder.height(), 7);
+    /// ```
+    #[inline]
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        Self::new(Cursor::new(bytes))
+    }
+}
+
+impl Decoder<Cursor<String>> {
+    /// Decodes all images read from `reader` as frames.
+    ///
+    /// Some tools store the frames of an animation as consecutive images with
+    /// numbered names (e.g., `frame0_bits`, `frame1_bits`, ...) in one file.
+    /// Each image ends at the line which ends with `};`, and the frames are
+    /// returned in the order of the file along with their headers.
+    ///
+    /// `0` represents a white pixel and `1` represents a black pixel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs while decoding any of the images.
+    /// See [`Decoder::new`] and [`Decoder::decode`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/frames.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let frames = Decoder::decode_frames(reader).unwrap();
+    /// assert_eq!(frames.len(), 2);
+    /// assert_eq!(frames[0].0.name(), "frame0");
+    /// assert_eq!(frames[1].0.name(), "frame1");
+    /// ```
+    pub fn decode_frames(reader: impl BufRead) -> Result<Vec<(Header, Vec<u8>)>, Error> {
+        split_frames(reader)
+            .map(|decoder| {
+                let decoder = decoder?;
+                let header = decoder.metadata();
+                Ok((header, decoder.decode_to_vec()?))
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "image")]
+    /// Decodes all images read from `reader` as [`Frame`](image::Frame)s
+    /// with `delay`.
+    ///
+    /// Each frame is decoded as [`Decoder::decode_rgba`] does. See
+    /// [`Decoder::decode_frames`] for how the images are separated.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurs while decoding any of the images.
+    /// See [`Decoder::new`] and [`Decoder::decode`] for the details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{fs::File, io::BufReader};
+    /// #
+    /// # use image::Delay;
+    /// # use xbm::Decoder;
+    /// #
+    /// let reader = File::open("tests/data/frames.xbm")
+    ///     .map(BufReader::new)
+    ///     .unwrap();
+    /// let delay = Delay::from_numer_denom_ms(100, 1);
+    /// let frames = Decoder::decode_image_frames(reader, delay).unwrap();
+    /// assert_eq!(frames.len(), 2);
+    /// assert_eq!(frames[0].delay(), delay);
+    /// assert_eq!(frames[0].buffer().dimensions(), (8, 7));
+    /// ```
+    pub fn decode_image_frames(
+        reader: impl BufRead,
+        delay: image::Delay,
+    ) -> image::ImageResult<Vec<image::Frame>> {
+        split_frames(reader)
+            .map(|decoder| {
+                let buffer = decoder.map_err(into_image_error)?.decode_rgba()?;
+                Ok(image::Frame::from_parts(buffer, 0, 0, delay))
+            })
+            .collect()
+    }
+}
+
+/// Limits on the image dimensions.
+///
+/// The default limits are generous but finite: the width and the height are
+/// limited to 65536 pixels, and the number of pixels is limited to 512 Mi.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Limits {
+    /// The maximum width of the image.
+    pub max_width: u32,
+
+    /// The maximum height of the image.
+    pub max_height: u32,
+
+    /// The maximum number of the pixels of the image (the width multiplied by
+    /// the height).
+    pub max_pixels: u64,
+}
+
+impl Limits {
+    /// Creates a new `Limits` which does not limit anything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::decode::Limits;
+    /// #
+    /// let limits = Limits::no_limits();
+    /// assert_eq!(limits.max_width, u32::MAX);
+    /// assert_eq!(limits.max_height, u32::MAX);
+    /// assert_eq!(limits.max_pixels, u64::MAX);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn no_limits() -> Self {
+        Self {
+            max_width: u32::MAX,
+            max_height: u32::MAX,
+            max_pixels: u64::MAX,
+        }
+    }
+
+    /// Checks the image dimensions against the limits.
+    fn check(&self, width: u32, height: u32) -> Result<(), Error> {
+        let pixels = u64::from(width) * u64::from(height);
+        if width > self.max_width || height > self.max_height || pixels > self.max_pixels {
+            return Err(Error::LimitsExceeded);
+        }
+        Ok(())
+    }
+}
+
+impl Default for Limits {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_width: 1 << 16,
+            max_height: 1 << 16,
+            max_pixels: 1 << 29,
+        }
+    }
+}
+
+/// The state for converting the elements of the array into the packed bytes.
+struct ElementParser {
+    format: Format,
+    bytes_per_line: usize,
+    elements_per_line: usize,
+    bit_order: BitOrder,
+    column: usize,
+    checksum: Option<(u32, Crc32)>,
+}
+
+impl ElementParser {
+    /// Parses `token` at `offset` as an element, and calls `f` with each of
+    /// the packed bytes of the element.
+    ///
+    /// In the X10 format, the padding byte at the end of each row is skipped.
+    fn push(&mut self, token: &str, offset: usize, f: &mut impl FnMut(u8)) -> Result<(), Error> {
+        let format = self.format;
+        let element = match format {
+            Format::X10 => parse_hex_short(token, offset)?,
+            Format::X11 => u16::from(parse_hex_byte(token, offset)?),
+        };
+        for pixels_byte in element
+            .to_le_bytes()
+            .into_iter()
+            .take(format.element_size())
+        {
+            if let Some((_, hasher)) = &mut self.checksum {
+                hasher.update(pixels_byte);
+            }
+            if self.column < self.bytes_per_line {
+                f(self.bit_order.convert(pixels_byte));
+            }
+            self.column += 1;
+        }
+        if self.column == self.elements_per_line * format.element_size() {
+            self.column = 0;
+        }
+        Ok(())
+    }
+
+    /// Verifies the CRC-32 checksum if it is enabled.
+    const fn finish(self) -> Result<(), Error> {
+        if let Some((expected, hasher)) = self.checksum {
+            let actual = hasher.finalize();
+            if actual != expected {
+                return Err(Error::ChecksumMismatch { expected, actual });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Format of an XBM image.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Format {
+    /// The X10 format, whose array consists of 16-bit `short` values.
+    X10,
+
+    /// The X11 format, whose array consists of 8-bit `char` values.
+    X11,
+}
+
+impl Format {
+    /// Returns the number of the bytes per element of the array.
+    const fn element_size(self) -> usize {
+        match self {
+            Self::X10 => 2,
+            Self::X11 => 1,
+        }
+    }
+}
+
+/// Header of an XBM image.
+///
+/// This holds the values parsed from the `#define` directives and the name of
+/// the image, independent of the reader.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Header {
+    name: String,
+    width: u32,
+    height: u32,
+    x_hot: Option<u32>,
+    y_hot: Option<u32>,
+    crc32: Option<u32>,
+}
+
+impl Header {
+    /// Returns the name of the image.
+    #[must_use]
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the width of the image.
+    #[must_use]
+    #[inline]
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the image.
+    #[must_use]
+    #[inline]
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the _x_ coordinate of the hotspot.
+    ///
+    /// Returns [`None`] if the value is not defined.
+    #[must_use]
+    #[inline]
+    pub const fn x_hot(&self) -> Option<u32> {
+        self.x_hot
+    }
+
+    /// Returns the _y_ coordinate of the hotspot.
+    ///
+    /// Returns [`None`] if the value is not defined.
+    #[must_use]
+    #[inline]
+    pub const fn y_hot(&self) -> Option<u32> {
+        self.y_hot
+    }
+
+    /// Returns the coordinates of the hotspot as `(x, y)`.
+    ///
+    /// Returns [`None`] if the hotspot is not defined.
+    #[must_use]
+    #[inline]
+    pub const fn hotspot(&self) -> Option<(u32, u32)> {
+        match (self.x_hot, self.y_hot) {
+            (Some(x), Some(y)) => Some((x, y)),
+            _ => None,
+        }
+    }
+
+    /// Returns the CRC-32 checksum of the packed bytes of the image.
+    ///
+    /// Returns [`None`] if the value is not defined.
+    #[must_use]
+    #[inline]
+    pub const fn crc32(&self) -> Option<u32> {
+        self.crc32
+    }
+}
+
+/// An iterator over the rows of an XBM image.
+///
+/// This is created by [`Decoder::rows`].
+#[derive(Debug)]
+pub struct Rows {
+    pixels: Vec<u8>,
+    width: usize,
+    row: usize,
+    err: Option<Error>,
+}
+
+impl Iterator for Rows {
+    type Item = Result<Vec<u8>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.err.take() {
+            return Some(Err(err));
+        }
+        let start = self.row * self.width;
+        let row = self.pixels.get(start..(start + self.width))?;
+        self.row += 1;
+        Some(Ok(row.to_vec()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if self.err.is_some() {
+            1
+        } else {
+            self.pixels.len() / self.width - self.row
+        };
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Rows {}
+
+impl FusedIterator for Rows {}
+
+/// Reads the XBM image from the file at `path` and decodes it.
+///
+/// This is a shorthand for [`Decoder::new`] with the file wrapped in a
+/// [`BufReader`], followed by [`Decoder::decode_to_vec`].
+/// Returns the header of the image along with the pixels, where `0`
+/// represents a white pixel and `1` represents a black pixel.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the file cannot be opened, or if an error occurs
+/// during decoding. See [`Decoder::new`] and [`Decoder::decode`] for the
+/// details.
+///
+/// # Examples
+///
+/// ```
+/// let (header, pixels) = xbm::decode::read_from_path("tests/data/basic.xbm").unwrap();
+/// assert_eq!(header.name(), "image");
+/// assert_eq!((header.width(), header.height()), (8, 7));
+/// assert_eq!(pixels.len(), 56);
+/// ```
+pub fn read_from_path(path: impl AsRef<Path>) -> Result<(Header, Vec<u8>), Error> {
+    let inner = |path: &Path| -> Result<(Header, Vec<u8>), Error> {
+        let reader = File::open(path).map(BufReader::new)?;
+        let decoder = Decoder::new(reader)?;
+        let header = decoder.metadata();
+        Ok((header, decoder.decode_to_vec()?))
+    };
+    inner(path.as_ref())
+}
+
+/// The table which maps each byte to the pixels, where `0` represents a white
+/// pixel and `1` represents a black pixel.
+const PIXELS: [[u8; 8]; 256] = expand_bits(0, 1);
+
+/// The table which maps each byte to the pixels, where `1` represents a white
+/// pixel and `0` represents a black pixel.
+const INVERTED_PIXELS: [[u8; 8]; 256] = expand_bits(1, 0);
+
+/// The table which maps each byte to the pixels, where `false` represents a
+/// white pixel and `true` represents a black pixel.
+const BOOLS: [[bool; 8]; 256] = expand_bits(false, true);
+
+/// The table which maps each byte to the pixels, where `true` represents a
+/// white pixel and `false` represents a black pixel.
+const INVERTED_BOOLS: [[bool; 8]; 256] = expand_bits(true, false);
+
+#[cfg(feature = "image")]
+/// The table which maps each byte to the pixels of [`image::ColorType::L8`].
+const LUMA: [[u8; 8]; 256] = expand_bits(u8::MAX, u8::MIN);
+
+#[cfg(feature = "image")]
+/// The table which maps each byte to the pixels of [`image::ColorType::L8`]
+/// with the inverted polarity.
+const INVERTED_LUMA: [[u8; 8]; 256] = expand_bits(u8::MIN, u8::MAX);
+
+/// Creates the table which maps each byte to the eight pixels, from the least
+/// significant bit to the most significant bit.
+const fn expand_bits<T: Copy>(zero: T, one: T) -> [[T; 8]; 256] {
+    let mut table = [[zero; 8]; 256];
+    let mut byte = 0;
+    while byte < table.len() {
+        let mut i = 0;
+        while i < 8 {
+            table[byte][i] = if (byte >> i) & 1 == 0 { zero } else { one };
+            i += 1;
+        }
+        byte += 1;
+    }
+    table
+}
+
+/// Returns the number of pixels of the image with `width` and `height`, or
+/// [`None`] if it does not fit in [`usize`].
+pub(crate) fn pixel_count(width: u32, height: u32) -> Option<usize> {
+    let width = usize::try_from(width).ok()?;
+    let height = usize::try_from(height).ok()?;
+    width.checked_mul(height)
+}
+
+/// Returns the number of pixels represented by `len` packed bytes of the image
+/// with `width`.
+fn packed_pixels(len: usize, width: usize) -> usize {
+    let bytes_per_line = bytes_per_line(width);
+    len.checked_div(bytes_per_line)
+        .map(|lines| {
+            lines
+                .saturating_mul(width)
+                .saturating_add((len % bytes_per_line) * 8)
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the number of the packed bytes per row of the image with `width`.
+///
+/// Unlike `(width + 7) / 8`, this does not overflow.
+const fn bytes_per_line(width: usize) -> usize {
+    if width % 8 == 0 {
+        width / 8
+    } else {
+        width / 8 + 1
+    }
+}
+
+/// Parses the value of the `#define` directive from the remaining tokens.
+///
+/// In addition to an integer literal, this accepts a parenthesized addition or
+/// multiplication of two integer literals, such as `(4 * 2)`.
+fn parse_define_value<'a>(
+    tokens: impl Iterator<Item = &'a str>,
+    line_no: usize,
+    line: &str,
+) -> Result<u32, Error> {
+    let mut tokens = tokens;
+    match tokens.next() {
+        Some(value) if !value.starts_with('(') => {
+            if tokens.next().is_some() {
+                return Err(invalid_header(line_no, line));
+            }
+            Ok(parse_value(value)?)
+        }
+        Some(first) => {
+            let expr = tokens.fold(String::from(first), |mut expr, token| {
+                expr.push_str(token);
+                expr
+            });
+            let Some(expr) = expr.strip_prefix('(').and_then(|e| e.strip_suffix(')')) else {
+                return Err(invalid_header(line_no, line));
+            };
+            let value = if let Some((lhs, rhs)) = expr.split_once('*') {
+                parse_value(lhs)?.checked_mul(parse_value(rhs)?)
+            } else if let Some((lhs, rhs)) = expr.split_once('+') {
+                parse_value(lhs)?.checked_add(parse_value(rhs)?)
+            } else {
+                Some(parse_value(expr)?)
+            };
+            value.ok_or_else(|| invalid_header(line_no, line))
+        }
+        None => Err(invalid_header(line_no, line)),
+    }
+}
+
+/// Returns `true` if `line` is a preprocessor conditional, an `#include`, a
+/// `#define` of an identifier other than the ones of the image `name`, or a
+/// `typedef`.
+///
+/// These lines define the type of the array, such as the ones written by
+/// [`Encoder::portable_types`](crate::Encoder::portable_types).
+fn is_type_definition(line: &str, name: &str) -> bool {
+    let line = line.trim();
+    if let Some(directive) = line.strip_prefix('#') {
+        let mut tokens = directive.split_whitespace();
+        return match tokens.next() {
+            Some("if" | "ifdef" | "ifndef" | "elif" | "else" | "endif" | "include") => true,
+            Some("define") => tokens
+                .next()
+                .is_some_and(|key| key.strip_prefix(name).map_or(true, |k| !k.starts_with('_'))),
+            _ => false,
+        };
+    }
+    line.strip_prefix("typedef")
+        .is_some_and(|l| l.starts_with(char::is_whitespace) && l.ends_with(';'))
+}
+
+/// Parses the array declaration before the opening brace, such as
+/// `static unsigned char name_bits[] =`, and returns its format.
+fn parse_declaration(declaration: &str, name: &str) -> Option<Format> {
+    let (lhs, rhs) = declaration.split_once('=')?;
+    if !rhs.trim().is_empty() {
+        return None;
+    }
+    let mut tokens = lhs.split_whitespace();
+    if tokens.next()? != "static" {
+        return None;
+    }
+    let format = match tokens.next()? {
+        "unsigned" => match tokens.next()? {
+            "char" => Format::X11,
+            "short" => Format::X10,
+            _ => return None,
+        },
+        "char" | "uint8_t" => Format::X11,
+        "short" | "uint16_t" => Format::X10,
+        _ => return None,
+    };
+    let bits = tokens.next()?;
+    (bits.strip_prefix(name) == Some("_bits[]") && tokens.next().is_none()).then_some(format)
+}
+
+/// Returns [`Error::InvalidImageSize`] for the image of `width` by `height`
+/// from which `decoded_pixels` pixels were decoded.
+const fn invalid_image_size(width: usize, height: usize, decoded_pixels: usize) -> Error {
+    Error::InvalidImageSize {
+        expected_pixels: width.saturating_mul(height),
+        decoded_pixels,
+    }
+}
+
+/// Returns [`Error::InvalidHeader`] for the line `line_no` whose text is
+/// `line`.
+fn invalid_header(line_no: usize, line: &str) -> Error {
+    Error::InvalidHeader {
+        line_no,
+        text: line.trim_end().into(),
+    }
+}
+
+/// Splits `line` into the tokens after `#define`.
+///
+/// Since the C preprocessor allows whitespace between `#` and `define`, this
+/// also accepts `# define`. Returns [`None`] if `line` is not a `#define`
+/// directive.
+pub(crate) fn split_define(line: &str) -> Option<SplitWhitespace<'_>> {
+    let rest = line
+        .trim_start()
+        .strip_prefix('#')?
+        .trim_start()
+        .strip_prefix("define")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.split_whitespace())
+}
+
+/// Returns `true` if `key` is the identifier of a coordinate of the hotspot.
+fn is_hotspot_key(key: &str) -> bool {
+    key.ends_with("_x_hot") || key.ends_with("_y_hot")
+}
+
+/// Returns `true` if `key` is the identifier of a `#define` directive which
+/// starts with `name` but is not recognized by the decoder.
+fn is_extra_key(key: &str, name: &str) -> bool {
+    key.strip_prefix(name)
+        .and_then(|k| k.strip_prefix('_'))
+        .is_some_and(|k| {
+            !k.is_empty() && !matches!(k, "width" | "height" | "x_hot" | "y_hot" | "crc32")
+        })
+}
+
+/// Parses a value of the `#define` directive expressed in either the decimal
+/// notation or the hexadecimal notation.
+pub(crate) fn parse_value(value: &str) -> Result<u32, ParseIntError> {
+    value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .map_or_else(|| value.parse(), |v| u32::from_str_radix(v, 16))
+}
+
+/// Reads the next line which is not blank into `buf`, reusing its allocation.
+///
+/// Returns `false` if the reader reaches EOF.
+///
+/// The number of the read bytes, including the skipped blank lines, is added
+/// to `offset`.
+fn read_non_blank_line(
+    reader: &mut impl BufRead,
+    buf: &mut String,
+    offset: &mut usize,
+) -> io::Result<bool> {
+    loop {
+        buf.clear();
+        let len = reader.read_line(buf)?;
+        if len == 0 {
+            return Ok(false);
+        }
+        *offset += len;
+        if !buf.trim().is_empty() {
+            return Ok(true);
+        }
+    }
+}
+
+/// Returns `true` if `byte` is an ASCII character which [`str::trim`]
+/// removes.
+fn is_space(byte: u8) -> bool {
+    byte.is_ascii() && char::from(byte).is_whitespace()
+}
+
+/// Returns an iterator over the `Decoder`s for the images read from `reader`.
+///
+/// Each image ends at the line which ends with `};`.
+fn split_frames(
+    mut reader: impl BufRead,
+) -> impl Iterator<Item = Result<Decoder<Cursor<String>>, Error>> {
+    let mut is_done = false;
+    iter::from_fn(move || {
+        let mut image = String::new();
+        while !is_done {
+            let mut line = String::new();
+            match reader.read_line(&mut line) {
+                Ok(0) => is_done = true,
+                Ok(_) => {}
+                Err(err) => {
+                    is_done = true;
+                    return Some(Err(err.into()));
+                }
+            }
+            image.push_str(&line);
+            if is_done || strip_comments(&line).trim_end().ends_with("};") {
+                if image.trim().is_empty() {
+                    continue;
+                }
+                // If the last image is not terminated, decoding it reports why.
+                return Some(Decoder::new(Cursor::new(image)));
+            }
+        }
+        None
+    })
+}
+
+/// Removes the C comments from `line`.
+///
+/// Each comment is replaced with the spaces of the same length, so that the
+/// byte offsets of the remaining code are preserved. A line comment continues
+/// until the end of the line.
+pub(crate) fn strip_comments(line: &str) -> Cow<'_, str> {
+    if !line.contains("/*") && !line.contains("//") {
+        return Cow::Borrowed(line);
+    }
+
+    let mut code = String::with_capacity(line.len());
+    let mut rest = line;
+    loop {
+        let block = rest.find("/*");
+        let comment = rest.find("//");
+        match (block, comment) {
+            (Some(b), c) if c.map_or(true, |c| b < c) => {
+                code.push_str(&rest[..b]);
+                let Some(end) = rest[b + 2..].find("*/") else {
+                    return Cow::Owned(code);
+                };
+                let end = b + 2 + end + 2;
+                code.extend(iter::repeat(' ').take(end - b));
+                rest = &rest[end..];
+            }
+            (_, Some(c)) => {
+                code.push_str(&rest[..c]);
+                let Some(end) = rest[c..].find('\n') else {
+                    return Cow::Owned(code);
+                };
+                code.extend(iter::repeat(' ').take(end));
+                rest = &rest[c + end..];
+            }
+            _ => {
+                code.push_str(rest);
+                return Cow::Owned(code);
+            }
+        }
+    }
+}
+
+/// Splits a line of the array into the hex byte values.
+///
+/// Each hex byte value is paired with its byte offset in `line`.
+fn split_line(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = usize::default();
+    let mut tokens = line
+        .split_terminator(',')
+        .map(move |token| {
+            let pos = start + (token.len() - token.trim_start().len());
+            start += token.len() + 1;
+            (pos, token.trim())
+        })
+        .peekable();
+    iter::from_fn(move || {
+        let token = tokens.next()?;
+        if tokens.peek().is_none() && token.1.is_empty() {
+            return None;
+        }
+        Some(token)
+    })
+}
 
-            if pos == buf_len {
-                Ok(())
-            } else {
-                Err(Error::InvalidImageSize(pos))
-            }
-        };
-        inner(self, buf.as_mut())
+/// Parses a byte value expressed in the C hexadecimal notation.
+fn parse_hex_byte(token: &str, offset: usize) -> Result<u8, Error> {
+    if !token.is_ascii()
+        || token.len() != 4
+        || !(token.starts_with("0x") || token.starts_with("0X"))
+    {
+        return Err(Error::InvalidHexByte {
+            value: token.into(),
+            offset,
+        });
     }
+    let byte = u8::from_str_radix(&token[2..], 16)?;
+    Ok(byte)
+}
 
-    #[allow(clippy::missing_panics_doc)]
-    /// Decodes the image into a newly allocated [`Vec`].
-    ///
-    /// `0` represents a white pixel and `1` represents a black pixel.
-    ///
-    /// # Errors
-    ///
-    /// Returns [`Err`] if any of the following are true:
-    ///
-    /// - The hex byte value is invalid.
-    /// - The image termination string is not `};`.
-    /// - The expected image dimensions and the actual image dimensions
-    ///   mismatch.
-    /// - An error occurs during I/O operations.
-    /// - An error occurs while parsing the hex byte value.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use std::{fs::File, io::BufReader};
-    /// #
-    /// # use xbm::Decoder;
-    /// #
-    /// // "B" (8x7)
-    /// let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
-    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
-    ///                  \x00\x00\x01\x00\x00\x01\x00\x00\
-    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
-    ///                  \x00\x00\x01\x00\x00\x01\x00\x00\
-    ///                  \x00\x00\x01\x01\x01\x00\x00\x00\
-    ///                  \x00\x00\x00\x00\x00\x00\x00\x00";
-    ///
-    /// let reader = File::open("tests/data/basic.xbm")
-    ///     .map(BufReader::new)
-    ///     .unwrap();
-    /// let decoder = Decoder::new(reader).unwrap();
-    ///
-    /// let buf = decoder.decode_to_vec().unwrap();
-    /// assert_eq!(buf, expected);
-    /// ```
-    #[inline]
-    pub fn decode_to_vec(self) -> Result<Vec<u8>, Error> {
-        let dimensions = usize::try_from(self.width())
-            .expect("width should be in the range of `usize`")
-            * usize::try_from(self.height()).expect("height should be in the range of `usize`");
-        let mut buf = vec![u8::default(); dimensions];
-        self.decode(&mut buf)?;
-        Ok(buf)
+/// Parses a short value expressed in the C hexadecimal notation.
+fn parse_hex_short(token: &str, offset: usize) -> Result<u16, Error> {
+    if !token.is_ascii()
+        || token.len() != 6
+        || !(token.starts_with("0x") || token.starts_with("0X"))
+    {
+        return Err(Error::InvalidHexByte {
+            value: token.into(),
+            offset,
+        });
     }
+    let short = u16::from_str_radix(&token[2..], 16)?;
+    Ok(short)
 }
 
 #[cfg(feature = "image")]
 impl<R: BufRead + Seek> image::ImageDecoder for Decoder<R> {
     #[inline]
     fn dimensions(&self) -> (u32, u32) {
-        (self.width(), self.height())
+        Self::dimensions(self)
     }
 
     #[inline]
@@ -469,23 +3320,27 @@ impl<R: BufRead + Seek> image::ImageDecoder for Decoder<R> {
         ColorType::L8
     }
 
-    fn read_image(self, buf: &mut [u8]) -> image::ImageResult<()> {
-        use image::{
-            error::{DecodingError, ImageFormatHint},
-            ImageError,
-        };
+    /// Returns [`None`] because XBM images do not have an ICC profile.
+    #[inline]
+    fn icc_profile(&mut self) -> image::ImageResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
 
-        self.decode(buf).map_err(|err| match err {
-            Error::Io(err) => ImageError::IoError(err),
-            err => ImageError::Decoding(DecodingError::new(
-                ImageFormatHint::Name(String::from("XBM")),
-                err,
-            )),
-        })?;
-        debug_assert!(!buf.iter().any(|&p| p > 1));
-        buf.iter_mut()
-            .for_each(|p| *p = if p == &0 { u8::MAX } else { u8::MIN });
-        Ok(())
+    /// Returns the number of the pixels, since each pixel is decoded into a
+    /// byte.
+    ///
+    /// This does not overflow because both the width and the height are
+    /// [`u32`].
+    #[inline]
+    fn total_bytes(&self) -> u64 {
+        let (width, height) = self.dimensions();
+        u64::from(width) * u64::from(height)
+    }
+
+    fn read_image(self, buf: &mut [u8]) -> image::ImageResult<()> {
+        let table = if self.invert { &INVERTED_LUMA } else { &LUMA };
+        self.decode_with_table(buf, table, false)
+            .map_err(into_image_error)
     }
 
     #[inline]
@@ -493,6 +3348,15 @@ impl<R: BufRead + Seek> image::ImageDecoder for Decoder<R> {
         (*self).read_image(buf)
     }
 
+    fn set_limits(&mut self, mut limits: image::Limits) -> image::ImageResult<()> {
+        use image::LimitSupport;
+
+        limits.check_support(&LimitSupport::default())?;
+        let (width, height) = self.dimensions();
+        limits.check_dimensions(width, height)?;
+        limits.reserve(self.total_bytes())
+    }
+
     #[inline]
     fn original_color_type(&self) -> image::ExtendedColorType {
         use image::ExtendedColorType;
@@ -501,24 +3365,117 @@ impl<R: BufRead + Seek> image::ImageDecoder for Decoder<R> {
     }
 }
 
+#[cfg(feature = "image")]
+/// Converts `err` into [`ImageError`](image::ImageError).
+fn into_image_error(err: Error) -> image::ImageError {
+    use image::{
+        error::{DecodingError, ImageFormatHint},
+        ImageError,
+    };
+
+    match err {
+        Error::Io(err) => ImageError::IoError(err),
+        err => ImageError::Decoding(DecodingError::new(
+            ImageFormatHint::Name(String::from("XBM")),
+            err,
+        )),
+    }
+}
+
 /// The error type indicating that an error occurred during decoding.
 #[derive(Debug)]
 pub enum Error {
     /// The header was invalid.
-    InvalidHeader,
+    InvalidHeader {
+        /// The one-based line number of the invalid line.
+        line_no: usize,
+
+        /// The text of the invalid line.
+        text: String,
+    },
+
+    /// Only one of the _x_ coordinate and the _y_ coordinate of the hotspot
+    /// was defined.
+    IncompleteHotspot,
+
+    /// The hotspot was defined before the width or the height.
+    HotspotBeforeDimensions,
+
+    /// The same `#define` directive was defined more than once.
+    ///
+    /// This contains the name of the duplicated field (e.g., `width`).
+    DuplicateDefine(&'static str),
+
+    /// Either the width or the height was `0`.
+    ZeroDimension,
+
+    /// The name of the image was different from the expected name.
+    UnexpectedName {
+        /// The expected name.
+        expected: String,
+
+        /// The name defined in the header.
+        found: String,
+    },
 
     /// The byte value expressed in the [C hexadecimal notation] which
     /// represents the pixels was invalid.
     ///
     /// [C hexadecimal notation]: https://en.wikipedia.org/wiki/Hexadecimal
-    InvalidHexByte(String),
+    InvalidHexByte {
+        /// The invalid hex byte value.
+        value: String,
+
+        /// The byte offset in the reader at which the value starts.
+        offset: usize,
+    },
 
     /// The image termination string was not `};`.
     InvalidTermination,
 
+    /// The reader reached EOF before the image termination string.
+    ///
+    /// This usually means that the file is truncated.
+    UnexpectedEof,
+
     /// The expected image dimensions and the actual image dimensions
     /// mismatched.
-    InvalidImageSize(usize),
+    InvalidImageSize {
+        /// The number of the pixels of the image (the width multiplied by the
+        /// height).
+        expected_pixels: usize,
+
+        /// The number of the pixels decoded from the array.
+        decoded_pixels: usize,
+    },
+
+    /// The number of the packed bytes in a line of the array and the number of
+    /// the packed bytes per row of the image mismatched.
+    RowLengthMismatch {
+        /// The zero-based index of the row.
+        row: usize,
+
+        /// The number of the packed bytes per row of the image.
+        expected: usize,
+
+        /// The number of the packed bytes in the line.
+        found: usize,
+    },
+
+    /// The CRC-32 checksum of the packed bytes of the image mismatched.
+    ChecksumMismatch {
+        /// The checksum defined in the header.
+        expected: u32,
+
+        /// The checksum computed from the image.
+        actual: u32,
+    },
+
+    /// The image dimensions exceeded the limits.
+    LimitsExceeded,
+
+    /// The number of pixels of the image exceeded the range of [`usize`].
+    ImageTooLarge,
 
     /// An error occurred during I/O operations.
     Io(io::Error),
@@ -527,14 +3484,218 @@ pub enum Error {
     ParseInt(ParseIntError),
 }
 
+impl Error {
+    /// Returns `true` if the header was invalid.
+    #[must_use]
+    #[inline]
+    pub const fn is_invalid_header(&self) -> bool {
+        matches!(self, Self::InvalidHeader { .. })
+    }
+
+    /// Returns `true` if only one of the coordinates of the hotspot was
+    /// defined.
+    #[must_use]
+    #[inline]
+    pub const fn is_incomplete_hotspot(&self) -> bool {
+        matches!(self, Self::IncompleteHotspot)
+    }
+
+    /// Returns `true` if the hotspot was defined before the image dimensions.
+    #[must_use]
+    #[inline]
+    pub const fn is_hotspot_before_dimensions(&self) -> bool {
+        matches!(self, Self::HotspotBeforeDimensions)
+    }
+
+    /// Returns `true` if the same `#define` directive was defined more than
+    /// once.
+    #[must_use]
+    #[inline]
+    pub const fn is_duplicate_define(&self) -> bool {
+        matches!(self, Self::DuplicateDefine(_))
+    }
+
+    /// Returns `true` if either the width or the height was `0`.
+    #[must_use]
+    #[inline]
+    pub const fn is_zero_dimension(&self) -> bool {
+        matches!(self, Self::ZeroDimension)
+    }
+
+    /// Returns `true` if the name of the image was different from the
+    /// expected name.
+    #[must_use]
+    #[inline]
+    pub const fn is_unexpected_name(&self) -> bool {
+        matches!(self, Self::UnexpectedName { .. })
+    }
+
+    /// Returns `true` if the hex byte value was invalid.
+    #[must_use]
+    #[inline]
+    pub const fn is_invalid_hex_byte(&self) -> bool {
+        matches!(self, Self::InvalidHexByte { .. })
+    }
+
+    /// Returns `true` if the image termination string was invalid.
+    #[must_use]
+    #[inline]
+    pub const fn is_invalid_termination(&self) -> bool {
+        matches!(self, Self::InvalidTermination)
+    }
+
+    /// Returns `true` if the reader reached EOF before the image termination
+    /// string.
+    #[must_use]
+    #[inline]
+    pub const fn is_unexpected_eof(&self) -> bool {
+        matches!(self, Self::UnexpectedEof)
+    }
+
+    /// Returns `true` if the image dimensions mismatched.
+    #[must_use]
+    #[inline]
+    pub const fn is_invalid_image_size(&self) -> bool {
+        matches!(self, Self::InvalidImageSize { .. })
+    }
+
+    /// Returns `true` if the length of a row mismatched.
+    #[must_use]
+    #[inline]
+    pub const fn is_row_length_mismatch(&self) -> bool {
+        matches!(self, Self::RowLengthMismatch { .. })
+    }
+
+    /// Returns `true` if the CRC-32 checksum mismatched.
+    #[must_use]
+    #[inline]
+    pub const fn is_checksum_mismatch(&self) -> bool {
+        matches!(self, Self::ChecksumMismatch { .. })
+    }
+
+    /// Returns `true` if the image dimensions exceeded the limits.
+    #[must_use]
+    #[inline]
+    pub const fn is_limits_exceeded(&self) -> bool {
+        matches!(self, Self::LimitsExceeded)
+    }
+
+    /// Returns `true` if the number of pixels of the image exceeded the range
+    /// of [`usize`].
+    #[must_use]
+    #[inline]
+    pub const fn is_image_too_large(&self) -> bool {
+        matches!(self, Self::ImageTooLarge)
+    }
+
+    /// Returns `true` if an error occurred during I/O operations.
+    #[must_use]
+    #[inline]
+    pub const fn is_io(&self) -> bool {
+        matches!(self, Self::Io(_))
+    }
+
+    /// Returns `true` if an error occurred while parsing an integer.
+    #[must_use]
+    #[inline]
+    pub const fn is_parse_int(&self) -> bool {
+        matches!(self, Self::ParseInt(_))
+    }
+
+    /// Returns the underlying I/O error if an error occurred during I/O
+    /// operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// #
+    /// # use xbm::decode::Error;
+    /// #
+    /// let err = Error::from(io::Error::from(io::ErrorKind::NotFound));
+    /// assert_eq!(err.as_io().unwrap().kind(), io::ErrorKind::NotFound);
+    /// assert!(Error::IncompleteHotspot.as_io().is_none());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn as_io(&self) -> Option<&io::Error> {
+        if let Self::Io(err) = self {
+            Some(err)
+        } else {
+            None
+        }
+    }
+
+    /// Converts into the underlying I/O error if an error occurred during I/O
+    /// operations.
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` if this is not an I/O error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io;
+    /// #
+    /// # use xbm::decode::Error;
+    /// #
+    /// let err = Error::from(io::Error::from(io::ErrorKind::NotFound));
+    /// assert_eq!(err.into_io().unwrap().kind(), io::ErrorKind::NotFound);
+    /// assert!(Error::IncompleteHotspot.into_io().is_err());
+    /// ```
+    #[inline]
+    pub fn into_io(self) -> Result<io::Error, Self> {
+        if let Self::Io(err) = self {
+            Ok(err)
+        } else {
+            Err(self)
+        }
+    }
+}
+
 impl fmt::Display for Error {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::InvalidHeader => write!(f, "invalid header"),
-            Self::InvalidHexByte(value) => write!(f, "invalid hex byte `{value}`"),
+            Self::InvalidHeader { line_no, text } => {
+                write!(f, "invalid header at line {line_no}: {text:?}")
+            }
+            Self::IncompleteHotspot => write!(f, "only one of `x_hot` and `y_hot` is defined"),
+            Self::HotspotBeforeDimensions => {
+                write!(f, "hotspot is defined before the image dimensions")
+            }
+            Self::DuplicateDefine(field) => write!(f, "`{field}` is defined more than once"),
+            Self::ZeroDimension => write!(f, "width or height of the image is `0`"),
+            Self::UnexpectedName { expected, found } => {
+                write!(f, "unexpected name `{found}` (expected `{expected}`)")
+            }
+            Self::InvalidHexByte { value, offset } => {
+                write!(f, "invalid hex byte `{value}` at offset `{offset}`")
+            }
             Self::InvalidTermination => write!(f, "invalid termination string"),
-            Self::InvalidImageSize(size) => write!(f, "invalid image size `{size}`"),
+            Self::UnexpectedEof => write!(f, "unexpected EOF before the termination string"),
+            Self::InvalidImageSize {
+                expected_pixels,
+                decoded_pixels,
+            } => write!(
+                f,
+                "invalid image size (decoded `{decoded_pixels}` pixels, expected `{expected_pixels}` pixels)"
+            ),
+            Self::RowLengthMismatch {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "invalid length of row `{row}` (expected `{expected}` bytes, found `{found}` bytes)"
+            ),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum mismatch (expected `{expected:#010X}`, actual `{actual:#010X}`)"
+            ),
+            Self::LimitsExceeded => write!(f, "image dimensions exceed the limits"),
+            Self::ImageTooLarge => write!(f, "number of pixels exceeds the range of `usize`"),
             Self::Io(err) => err.fmt(f),
             Self::ParseInt(err) => err.fmt(f),
         }
@@ -572,21 +3733,126 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn pixels_table() {
+        for (byte, pixels) in PIXELS.iter().enumerate() {
+            for (i, &pixel) in pixels.iter().enumerate() {
+                assert_eq!(pixel, u8::try_from((byte >> i) & 1).unwrap());
+            }
+        }
+        assert_eq!(PIXELS[0x00], [0; 8]);
+        assert_eq!(PIXELS[0x1C], [0, 0, 1, 1, 1, 0, 0, 0]);
+        assert_eq!(PIXELS[0xFF], [1; 8]);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn luma_table() {
+        for (byte, pixels) in LUMA.iter().enumerate() {
+            for (&luma, &pixel) in pixels.iter().zip(&PIXELS[byte]) {
+                assert_eq!(luma, if pixel == 0 { u8::MAX } else { u8::MIN });
+            }
+        }
+    }
+
+    #[test]
+    fn inverted_tables() {
+        for (byte, pixels) in INVERTED_PIXELS.iter().enumerate() {
+            for (&inverted, &pixel) in pixels.iter().zip(&PIXELS[byte]) {
+                assert_eq!(inverted, 1 - pixel);
+            }
+        }
+        #[cfg(feature = "image")]
+        for (byte, pixels) in INVERTED_LUMA.iter().enumerate() {
+            for (&inverted, &luma) in pixels.iter().zip(&LUMA[byte]) {
+                assert_eq!(inverted, u8::MAX - luma);
+            }
+        }
+    }
+
     #[test]
     fn debug_error() {
-        assert_eq!(format!("{:?}", Error::InvalidHeader), "InvalidHeader");
         assert_eq!(
-            format!("{:?}", Error::InvalidHexByte(String::from("0b00"))),
-            r#"InvalidHexByte("0b00")"#
+            format!(
+                "{:?}",
+                Error::InvalidHeader {
+                    line_no: 1,
+                    text: String::from("#define image_width")
+                }
+            ),
+            r##"InvalidHeader { line_no: 1, text: "#define image_width" }"##
+        );
+        assert_eq!(
+            format!("{:?}", Error::IncompleteHotspot),
+            "IncompleteHotspot"
+        );
+        assert_eq!(
+            format!("{:?}", Error::HotspotBeforeDimensions),
+            "HotspotBeforeDimensions"
+        );
+        assert_eq!(
+            format!("{:?}", Error::DuplicateDefine("width")),
+            r#"DuplicateDefine("width")"#
+        );
+        assert_eq!(format!("{:?}", Error::ZeroDimension), "ZeroDimension");
+        assert_eq!(
+            format!(
+                "{:?}",
+                Error::UnexpectedName {
+                    expected: String::from("cursor"),
+                    found: String::from("image")
+                }
+            ),
+            r#"UnexpectedName { expected: "cursor", found: "image" }"#
+        );
+        assert_eq!(
+            format!(
+                "{:?}",
+                Error::InvalidHexByte {
+                    value: String::from("0b00"),
+                    offset: 64
+                }
+            ),
+            r#"InvalidHexByte { value: "0b00", offset: 64 }"#
         );
         assert_eq!(
             format!("{:?}", Error::InvalidTermination),
             "InvalidTermination"
         );
+        assert_eq!(format!("{:?}", Error::UnexpectedEof), "UnexpectedEof");
+        assert_eq!(
+            format!(
+                "{:?}",
+                Error::InvalidImageSize {
+                    expected_pixels: 56,
+                    decoded_pixels: 48
+                }
+            ),
+            "InvalidImageSize { expected_pixels: 56, decoded_pixels: 48 }"
+        );
         assert_eq!(
-            format!("{:?}", Error::InvalidImageSize(usize::default())),
-            "InvalidImageSize(0)"
+            format!(
+                "{:?}",
+                Error::RowLengthMismatch {
+                    row: 1,
+                    expected: 2,
+                    found: 3
+                }
+            ),
+            "RowLengthMismatch { row: 1, expected: 2, found: 3 }"
+        );
+        assert_eq!(
+            format!(
+                "{:?}",
+                Error::ChecksumMismatch {
+                    expected: u32::MIN,
+                    actual: u32::MAX
+                }
+            ),
+            "ChecksumMismatch { expected: 0, actual: 4294967295 }"
         );
+        assert_eq!(format!("{:?}", Error::LimitsExceeded), "LimitsExceeded");
+        assert_eq!(format!("{:?}", Error::ImageTooLarge), "ImageTooLarge");
         assert_eq!(
             format!("{:?}", Error::Io(io::Error::from(ErrorKind::NotFound))),
             "Io(Kind(NotFound))"
@@ -598,19 +3864,100 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::too_many_lines)]
     fn display_error() {
-        assert_eq!(format!("{}", Error::InvalidHeader), "invalid header");
         assert_eq!(
-            format!("{}", Error::InvalidHexByte(String::from("0b00"))),
-            "invalid hex byte `0b00`"
+            format!(
+                "{}",
+                Error::InvalidHeader {
+                    line_no: 1,
+                    text: String::from("#define image_width")
+                }
+            ),
+            r##"invalid header at line 1: "#define image_width""##
+        );
+        assert_eq!(
+            format!("{}", Error::IncompleteHotspot),
+            "only one of `x_hot` and `y_hot` is defined"
+        );
+        assert_eq!(
+            format!("{}", Error::HotspotBeforeDimensions),
+            "hotspot is defined before the image dimensions"
+        );
+        assert_eq!(
+            format!("{}", Error::DuplicateDefine("width")),
+            "`width` is defined more than once"
+        );
+        assert_eq!(
+            format!("{}", Error::ZeroDimension),
+            "width or height of the image is `0`"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                Error::UnexpectedName {
+                    expected: String::from("cursor"),
+                    found: String::from("image")
+                }
+            ),
+            "unexpected name `image` (expected `cursor`)"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                Error::InvalidHexByte {
+                    value: String::from("0b00"),
+                    offset: 64
+                }
+            ),
+            "invalid hex byte `0b00` at offset `64`"
         );
         assert_eq!(
             format!("{}", Error::InvalidTermination),
             "invalid termination string"
         );
         assert_eq!(
-            format!("{}", Error::InvalidImageSize(usize::default())),
-            "invalid image size `0`"
+            format!("{}", Error::UnexpectedEof),
+            "unexpected EOF before the termination string"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                Error::InvalidImageSize {
+                    expected_pixels: 56,
+                    decoded_pixels: 48
+                }
+            ),
+            "invalid image size (decoded `48` pixels, expected `56` pixels)"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                Error::RowLengthMismatch {
+                    row: 1,
+                    expected: 2,
+                    found: 3
+                }
+            ),
+            "invalid length of row `1` (expected `2` bytes, found `3` bytes)"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                Error::ChecksumMismatch {
+                    expected: u32::MIN,
+                    actual: u32::MAX
+                }
+            ),
+            "checksum mismatch (expected `0x00000000`, actual `0xFFFFFFFF`)"
+        );
+        assert_eq!(
+            format!("{}", Error::LimitsExceeded),
+            "image dimensions exceed the limits"
+        );
+        assert_eq!(
+            format!("{}", Error::ImageTooLarge),
+            "number of pixels exceeds the range of `usize`"
         );
         assert_eq!(
             format!("{}", Error::Io(io::Error::from(ErrorKind::NotFound))),
@@ -624,10 +3971,51 @@ mod tests {
 
     #[test]
     fn source_error() {
-        assert!(Error::InvalidHeader.source().is_none());
-        assert!(Error::InvalidHexByte(String::default()).source().is_none());
+        assert!(Error::InvalidHeader {
+            line_no: 1,
+            text: String::default()
+        }
+        .source()
+        .is_none());
+        assert!(Error::IncompleteHotspot.source().is_none());
+        assert!(Error::HotspotBeforeDimensions.source().is_none());
+        assert!(Error::DuplicateDefine("width").source().is_none());
+        assert!(Error::ZeroDimension.source().is_none());
+        assert!(Error::UnexpectedName {
+            expected: String::default(),
+            found: String::default()
+        }
+        .source()
+        .is_none());
+        assert!(Error::InvalidHexByte {
+            value: String::default(),
+            offset: usize::default()
+        }
+        .source()
+        .is_none());
         assert!(Error::InvalidTermination.source().is_none());
-        assert!(Error::InvalidImageSize(usize::default()).source().is_none());
+        assert!(Error::UnexpectedEof.source().is_none());
+        assert!(Error::InvalidImageSize {
+            expected_pixels: usize::default(),
+            decoded_pixels: usize::default()
+        }
+        .source()
+        .is_none());
+        assert!(Error::RowLengthMismatch {
+            row: usize::default(),
+            expected: usize::default(),
+            found: usize::default()
+        }
+        .source()
+        .is_none());
+        assert!(Error::ChecksumMismatch {
+            expected: u32::default(),
+            actual: u32::default()
+        }
+        .source()
+        .is_none());
+        assert!(Error::LimitsExceeded.source().is_none());
+        assert!(Error::ImageTooLarge.source().is_none());
         assert!(Error::Io(io::Error::from(ErrorKind::NotFound))
             .source()
             .unwrap()
@@ -638,6 +4026,68 @@ mod tests {
             .is::<ParseIntError>());
     }
 
+    #[test]
+    fn error_predicates() {
+        assert!(Error::InvalidHeader {
+            line_no: 1,
+            text: String::default()
+        }
+        .is_invalid_header());
+        assert!(!Error::InvalidHeader {
+            line_no: 1,
+            text: String::default()
+        }
+        .is_io());
+        assert!(Error::IncompleteHotspot.is_incomplete_hotspot());
+        assert!(Error::HotspotBeforeDimensions.is_hotspot_before_dimensions());
+        assert!(Error::DuplicateDefine("width").is_duplicate_define());
+        assert!(Error::ZeroDimension.is_zero_dimension());
+        assert!(Error::UnexpectedName {
+            expected: String::default(),
+            found: String::default()
+        }
+        .is_unexpected_name());
+        assert!(Error::InvalidHexByte {
+            value: String::default(),
+            offset: usize::default()
+        }
+        .is_invalid_hex_byte());
+        assert!(Error::InvalidTermination.is_invalid_termination());
+        assert!(Error::UnexpectedEof.is_unexpected_eof());
+        assert!(Error::InvalidImageSize {
+            expected_pixels: usize::default(),
+            decoded_pixels: usize::default()
+        }
+        .is_invalid_image_size());
+        assert!(Error::RowLengthMismatch {
+            row: usize::default(),
+            expected: usize::default(),
+            found: usize::default()
+        }
+        .is_row_length_mismatch());
+        assert!(Error::ChecksumMismatch {
+            expected: u32::default(),
+            actual: u32::default()
+        }
+        .is_checksum_mismatch());
+        assert!(Error::LimitsExceeded.is_limits_exceeded());
+        assert!(Error::ImageTooLarge.is_image_too_large());
+        assert!(Error::Io(io::Error::from(ErrorKind::NotFound)).is_io());
+        assert!(!Error::Io(io::Error::from(ErrorKind::NotFound)).is_invalid_header());
+        assert!(Error::ParseInt(u32::from_str("").unwrap_err()).is_parse_int());
+    }
+
+    #[test]
+    fn io_error_accessors() {
+        let err = Error::Io(io::Error::from(ErrorKind::NotFound));
+        assert_eq!(err.as_io().unwrap().kind(), ErrorKind::NotFound);
+        assert_eq!(err.into_io().unwrap().kind(), ErrorKind::NotFound);
+
+        let err = Error::IncompleteHotspot;
+        assert!(err.as_io().is_none());
+        assert!(err.into_io().unwrap_err().is_incomplete_hotspot());
+    }
+
     #[test]
     fn from_io_error_to_error() {
         let err = io::Error::from(ErrorKind::NotFound);
@@ -4,12 +4,41 @@
 
 //! Encodes XBM images.
 
-use std::io::{self, ErrorKind, Write};
+use std::{
+    fs::File,
+    io::{self, BufWriter, ErrorKind, Write},
+    iter,
+    num::NonZeroU32,
+    path::Path,
+};
+
+use crate::{crc32::Crc32, decode, BitOrder};
 
 /// Encoder for XBM images.
 #[derive(Debug)]
 pub struct Encoder<W: Write> {
     writer: W,
+    options: Options,
+    index: usize,
+}
+
+#[allow(clippy::struct_excessive_bools)]
+/// The options of [`Encoder`].
+#[derive(Clone, Debug)]
+struct Options {
+    crc32: bool,
+    trailing_newline: bool,
+    element_type: ElementType,
+    bit_order: BitOrder,
+    visual_grid: bool,
+    wrap_per_row: bool,
+    portable_types: bool,
+    index_comments: bool,
+    pad_width_to_byte: bool,
+    brace_on_next_line: bool,
+    leading_comment: Option<String>,
+    array_comment: Option<String>,
+    indent: Option<String>,
 }
 
 impl<W: Write> Encoder<W> {
@@ -25,27 +54,1161 @@ impl<W: Write> Encoder<W> {
     /// ```
     #[inline]
     pub const fn new(writer: W) -> Self {
-        Self { writer }
+        Self {
+            writer,
+            options: Options {
+                crc32: false,
+                trailing_newline: true,
+                element_type: ElementType::U8,
+                bit_order: BitOrder::Lsb,
+                visual_grid: false,
+                wrap_per_row: false,
+                portable_types: false,
+                index_comments: false,
+                pad_width_to_byte: false,
+                brace_on_next_line: false,
+                leading_comment: None,
+                array_comment: None,
+                indent: None,
+            },
+            index: 0,
+        }
+    }
+
+    /// Sets whether to write the CRC-32 checksum of the packed bytes of the
+    /// image as `#define <name>_crc32 <value>`.
+    ///
+    /// The checksum is written after the hotspot. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).emit_crc32(true);
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert!(String::from_utf8(buf)
+    ///     .unwrap()
+    ///     .contains("#define image_crc32 0x"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn emit_crc32(mut self, emit: bool) -> Self {
+        self.options.crc32 = emit;
+        self
+    }
+
+    /// Sets whether to end the image termination string `};` with a newline.
+    ///
+    /// The default is `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).trailing_newline(false);
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert!(buf.ends_with(b"};"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.options.trailing_newline = trailing_newline;
+        self
+    }
+
+    /// Sets the type of the elements of the array.
+    ///
+    /// With [`ElementType::U16`] or [`ElementType::U32`], each row is padded
+    /// to a multiple of the element size, and the packed bytes are combined
+    /// into elements in little-endian order. Each element is written as a
+    /// hexadecimal literal zero-padded to the width of the type (e.g. `0x001C`
+    /// for [`ElementType::U16`]), without an integer suffix. The default is
+    /// [`ElementType::U8`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::{encode::ElementType, Encoder};
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).element_type(ElementType::U16);
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert!(String::from_utf8(buf)
+    ///     .unwrap()
+    ///     .contains("static unsigned short image_bits[] = {"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn element_type(mut self, element_type: ElementType) -> Self {
+        self.options.element_type = element_type;
+        self
+    }
+
+    /// Sets the order of the pixels within each byte of the array.
+    ///
+    /// With [`BitOrder::Msb`], the leftmost pixel is written in the most
+    /// significant bit of each byte, which is not the order defined by XBM.
+    /// This is only useful for the consumers which expect it. The CRC-32
+    /// checksum is computed from the bytes as written in the array. The
+    /// default is [`BitOrder::Lsb`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::{BitOrder, Encoder};
+    /// #
+    /// let pixels = [0, 0, 1, 1, 1, 0, 0, 0];
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).bit_order(BitOrder::Msb);
+    /// encoder.encode(pixels, "image", 8, 1, None, None).unwrap();
+    /// assert!(String::from_utf8(buf).unwrap().contains("0x38,"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn bit_order(mut self, bit_order: BitOrder) -> Self {
+        self.options.bit_order = bit_order;
+        self
+    }
+
+    /// Sets whether to write each row of the image on its own line, followed
+    /// by a comment rendering the row as a visual grid.
+    ///
+    /// In the comment, `#` represents a black pixel and `.` represents a white
+    /// pixel. The comments are ignored when decoding. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).emit_visual_grid(true);
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert!(String::from_utf8(buf)
+    ///     .unwrap()
+    ///     .contains("    0x24, /* ..#..#.. */\n"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn emit_visual_grid(mut self, emit: bool) -> Self {
+        self.options.visual_grid = emit;
+        self
+    }
+
+    /// Sets whether to write each row of the image on its own line.
+    ///
+    /// By default, the lines of the array have a fixed number of the
+    /// elements regardless of the width of the image. This reproduces the
+    /// layout of the images written by hand or by the `bitmap` program. This
+    /// is implied by [`Encoder::emit_visual_grid`]. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).wrap_per_row(true);
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert!(String::from_utf8(buf)
+    ///     .unwrap()
+    ///     .contains("{\n    0x00,\n    0x1C,\n    0x24,\n"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn wrap_per_row(mut self, enable: bool) -> Self {
+        self.options.wrap_per_row = enable;
+        self
+    }
+
+    /// Sets whether to declare the array with the fixed width integer type of
+    /// `<stdint.h>` (e.g. `uint8_t`) instead of the plain C type.
+    ///
+    /// The array declaration is preceded by a guarded `#include <stdint.h>`
+    /// for C99 or later, and by a fallback `typedef` otherwise, so that the
+    /// generated file compiles across C standards. The fallback is skipped if
+    /// the limit macro of the type (e.g. `UINT8_MAX`) is defined, that is,
+    /// `<stdint.h>` has already been included. The fallback for `uint32_t` is
+    /// `unsigned long`, since `unsigned int` may be 16-bit. The type is
+    /// determined by [`Encoder::element_type`]. The default is `false`.
+    ///
+    /// [`Decoder`](crate::Decoder) skips these lines, and reads the arrays of
+    /// `uint8_t` and `uint16_t`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).portable_types(true);
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// let image = String::from_utf8(buf).unwrap();
+    /// assert!(image.contains("typedef unsigned char uint8_t;"));
+    /// assert!(image.contains("static uint8_t image_bits[] = {"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn portable_types(mut self, portable: bool) -> Self {
+        self.options.portable_types = portable;
+        self
+    }
+
+    /// Encodes the binary image `buf`.
+    ///
+    /// `0` represents a white pixel and `1` represents a black pixel.
+    ///
+    /// `name` accepts a string which follow the specification in [Unicode
+    /// Standard Annex #31], but it is recommended that `name` be restricted to
+    /// the ASCII subset of `XID_Start` and `XID_Continue`.
+    ///
+    /// Any `width` is accepted. If `width` is not a multiple of 8, each row is
+    /// padded with white pixels, and the declared width is not byte-aligned
+    /// unless [`Encoder::pad_width_to_byte`] is enabled.
+    ///
+    /// The hotspot is written only if both `x_hot` and `y_hot` are [`Some`].
+    /// A coordinate of `Some(0)` is written as `0`, which is distinct from
+    /// [`None`], so the hotspot at the origin is preserved.
+    ///
+    /// The writer is flushed after the image is written, so the image is
+    /// complete even if the writer is a [`BufWriter`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `buf` contains values other than `0` and `1`.
+    /// - `name` is not a valid C identifier.
+    /// - Either `width` or `height` is `0`.
+    /// - Only one of `x_hot` and `y_hot` is [`Some`].
+    /// - An error occurs during I/O operations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `buf` and the image dimensions (the width
+    /// multiplied by the height) are different.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = [u8::default(); 132];
+    /// let encoder = Encoder::new(buf.as_mut_slice());
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert_eq!(buf, *include_bytes!("../tests/data/basic.xbm"));
+    /// ```
+    ///
+    /// [Unicode Standard Annex #31]: https://www.unicode.org/reports/tr31/
+    #[inline]
+    pub fn encode(
+        self,
+        buf: impl AsRef<[u8]>,
+        name: impl AsRef<str>,
+        width: u32,
+        height: u32,
+        x_hot: Option<u32>,
+        y_hot: Option<u32>,
+    ) -> Result<(), Error> {
+        let (Some(width), Some(height)) = (NonZeroU32::new(width), NonZeroU32::new(height)) else {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "either `width` or `height` is `0`",
+            ));
+        };
+        self.encode_nonzero(buf, name, width, height, x_hot, y_hot)
+    }
+
+    /// Encodes the binary image `buf` with the nonzero image dimensions.
+    ///
+    /// This is the same as [`Encoder::encode`] except that `width` and
+    /// `height` are [`NonZeroU32`], so they cannot be `0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `buf` contains values other than `0` and `1`.
+    /// - `name` is not a valid C identifier.
+    /// - Only one of `x_hot` and `y_hot` is [`Some`].
+    /// - An error occurs during I/O operations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `buf` and the image dimensions (the width
+    /// multiplied by the height) are different.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::num::NonZeroU32;
+    /// #
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let width = NonZeroU32::new(8).unwrap();
+    /// let height = NonZeroU32::new(7).unwrap();
+    /// let mut buf = [u8::default(); 132];
+    /// let encoder = Encoder::new(buf.as_mut_slice());
+    /// encoder
+    ///     .encode_nonzero(pixels, "image", width, height, None, None)
+    ///     .unwrap();
+    /// assert_eq!(buf, *include_bytes!("../tests/data/basic.xbm"));
+    /// ```
+    pub fn encode_nonzero(
+        self,
+        buf: impl AsRef<[u8]>,
+        name: impl AsRef<str>,
+        width: NonZeroU32,
+        height: NonZeroU32,
+        x_hot: Option<u32>,
+        y_hot: Option<u32>,
+    ) -> Result<(), Error> {
+        let hotspot = match (x_hot, y_hot) {
+            (Some(x), Some(y)) => Some((x, y)),
+            (None, None) => None,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "only one of `x_hot` and `y_hot` is `Some`",
+                ))
+            }
+        };
+        self.encode_with_hotspot(buf, name, width.get(), height.get(), hotspot)
+    }
+
+    /// Encodes the binary image `buf` with the hotspot given as `(x, y)`.
+    ///
+    /// This is the same as [`Encoder::encode`] except that the hotspot is
+    /// given as a pair, so only one of the coordinates cannot be specified.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `buf` contains values other than `0` and `1`.
+    /// - `name` is not a valid C identifier.
+    /// - Either `width` or `height` is `0`.
+    /// - An error occurs during I/O operations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `buf` and the image dimensions (the width
+    /// multiplied by the height) are different.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = [u8::default(); 176];
+    /// let encoder = Encoder::new(buf.as_mut_slice());
+    /// encoder
+    ///     .encode_with_hotspot(pixels, "image", 8, 7, Some((4, 3)))
+    ///     .unwrap();
+    /// assert_eq!(buf, *include_bytes!("../tests/data/hotspot.xbm"));
+    /// ```
+    pub fn encode_with_hotspot(
+        self,
+        buf: impl AsRef<[u8]>,
+        name: impl AsRef<str>,
+        width: u32,
+        height: u32,
+        hotspot: Option<(u32, u32)>,
+    ) -> Result<(), Error> {
+        let (x_hot, y_hot) = hotspot.unzip();
+        self.encode_counted(buf, name, width, height, x_hot, y_hot)
+            .map(|_| ())
+    }
+
+    #[allow(clippy::type_complexity)]
+    /// Encodes the multiple binary images `images` into a single file.
+    ///
+    /// Each element of `images` is a tuple of the name, the pixels, the width,
+    /// the height, and the hotspot of the image, which are the same as the
+    /// arguments of [`Encoder::encode_with_hotspot`]. The images are written
+    /// in order and separated by a blank line, so the result can be decoded
+    /// with [`Decoder::decode_frames`](crate::Decoder::decode_frames). The
+    /// leading comment is written only before the first image.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `buf` contains values other than `0` and `1`.
+    /// - `name` is not a valid C identifier.
+    /// - Either `width` or `height` is `0`.
+    /// - An error occurs during I/O operations.
+    ///
+    /// Encoding stops at the first image which fails, and the images before
+    /// it have already been written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the pixels and the image dimensions (the width
+    /// multiplied by the height) are different for any of the images.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::{Decoder, Encoder};
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    /// let mask = [1; 56];
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf);
+    /// encoder
+    ///     .encode_many(&[
+    ///         ("cursor", pixels, 8, 7, Some((4, 3))),
+    ///         ("cursor_mask", &mask, 8, 7, None),
+    ///     ])
+    ///     .unwrap();
+    ///
+    /// let images = Decoder::decode_frames(buf.as_slice()).unwrap();
+    /// assert_eq!(images.len(), 2);
+    /// assert_eq!(images[0].0.name(), "cursor");
+    /// assert_eq!(images[0].0.x_hot(), Some(4));
+    /// assert_eq!(images[1].0.name(), "cursor_mask");
+    /// assert_eq!(images[1].1, mask);
+    /// ```
+    pub fn encode_many(
+        mut self,
+        images: &[(&str, &[u8], u32, u32, Option<(u32, u32)>)],
+    ) -> Result<(), Error> {
+        for (i, &(name, buf, width, height, hotspot)) in images.iter().enumerate() {
+            let mut encoder = self.by_ref();
+            if i > 0 {
+                encoder.options.leading_comment = None;
+                writeln!(encoder.writer)?;
+            }
+            encoder.encode_with_hotspot(buf, name, width, height, hotspot)?;
+        }
+        Ok(())
+    }
+
+    /// Encodes the binary image `buf` and returns the number of the written
+    /// bytes.
+    ///
+    /// This is the same as [`Encoder::encode`] except that it returns the
+    /// number of the bytes written to the writer, which is useful for
+    /// building an index of the offsets into the concatenated images.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `buf` contains values other than `0` and `1`.
+    /// - `name` is not a valid C identifier.
+    /// - Either `width` or `height` is `0`.
+    /// - Only one of `x_hot` and `y_hot` is [`Some`].
+    /// - An error occurs during I/O operations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `buf` and the image dimensions (the width
+    /// multiplied by the height) are different.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf);
+    /// let len = encoder
+    ///     .encode_counted(pixels, "image", 8, 7, None, None)
+    ///     .unwrap();
+    /// assert_eq!(len, 132);
+    /// assert_eq!(len, buf.len());
+    /// ```
+    pub fn encode_counted(
+        self,
+        buf: impl AsRef<[u8]>,
+        name: impl AsRef<str>,
+        width: u32,
+        height: u32,
+        x_hot: Option<u32>,
+        y_hot: Option<u32>,
+    ) -> Result<usize, Error> {
+        let inner = |mut encoder: Encoder<ByteCounter<W>>,
+                     buf: &[u8],
+                     name: &str,
+                     width: u32,
+                     height: u32,
+                     x_hot: Option<u32>,
+                     y_hot: Option<u32>|
+         -> Result<usize, Error> {
+            assert_eq!(
+                Some(buf.len()),
+                decode::pixel_count(width, height),
+                "`buf` and the image dimensions are different"
+            );
+            let width = usize::try_from(width).expect("width should be in the range of `usize`");
+            let height = usize::try_from(height).expect("height should be in the range of `usize`");
+
+            if buf.iter().any(|&p| p > 1) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "`buf` contains values other than `0` and `1`",
+                ));
+            }
+            validate_header(name, width, height, x_hot, y_hot)?;
+            validate_array_comment(encoder.options.array_comment.as_deref())?;
+            validate_indent(encoder.options.indent.as_deref())?;
+
+            let bytes_per_line = encoder.bytes_per_line(width);
+            let mut packed = Vec::with_capacity(bytes_per_line * height);
+            for per_line in buf.chunks(width) {
+                pack_row(per_line, bytes_per_line, &mut packed);
+            }
+
+            let crc32 = encoder.options.crc32.then(|| {
+                Crc32::checksum(packed.iter().map(|&b| encoder.options.bit_order.convert(b)))
+            });
+            encoder.write_image(
+                name,
+                width,
+                height,
+                x_hot,
+                y_hot,
+                crc32,
+                &packed,
+                buf.chunks(width),
+            )?;
+            Ok(encoder.writer.count)
+        };
+        inner(
+            self.map_writer(ByteCounter::new),
+            buf.as_ref(),
+            name.as_ref(),
+            width,
+            height,
+            x_hot,
+            y_hot,
+        )
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Encodes the binary image from the iterator `pixels`.
+    ///
+    /// `true` represents a black pixel and `false` represents a white pixel.
+    /// The pixels are packed as they are yielded, so this does not require the
+    /// buffer of all pixels. See [`Encoder::encode`] for `name`, `x_hot`, and
+    /// `y_hot`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - The number of the pixels yielded by `pixels` and the image dimensions
+    ///   (the width multiplied by the height) are different.
+    /// - `name` is not a valid C identifier.
+    /// - Either `width` or `height` is `0`.
+    /// - Only one of `x_hot` and `y_hot` is [`Some`].
+    /// - An error occurs during I/O operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = (0..56).map(|i| {
+    ///     let (x, y) = (i % 8, i / 8);
+    ///     (2..=4).contains(&x) && y % 2 == 1 || (x == 2 || x == 5) && (y == 2 || y == 4)
+    /// });
+    ///
+    /// let mut buf = [u8::default(); 132];
+    /// let encoder = Encoder::new(buf.as_mut_slice());
+    /// encoder
+    ///     .encode_iter(pixels, "image", 8, 7, None, None)
+    ///     .unwrap();
+    /// assert_eq!(buf, *include_bytes!("../tests/data/basic.xbm"));
+    /// ```
+    pub fn encode_iter(
+        self,
+        pixels: impl IntoIterator<Item = bool>,
+        name: impl AsRef<str>,
+        width: u32,
+        height: u32,
+        x_hot: Option<u32>,
+        y_hot: Option<u32>,
+    ) -> Result<(), Error> {
+        let mut encoder = self;
+        let name = name.as_ref();
+        let dimensions = decode::pixel_count(width, height);
+        let width = usize::try_from(width).expect("width should be in the range of `usize`");
+        let height = usize::try_from(height).expect("height should be in the range of `usize`");
+        validate_header(name, width, height, x_hot, y_hot)?;
+        validate_array_comment(encoder.options.array_comment.as_deref())?;
+        validate_indent(encoder.options.indent.as_deref())?;
+        let mismatch = || {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "the number of the pixels and the image dimensions are different",
+            )
+        };
+        let dimensions = dimensions.ok_or_else(mismatch)?;
+
+        let bytes_per_line = encoder.bytes_per_line(width);
+        let mut packed = vec![u8::default(); bytes_per_line * height];
+        let mut count = usize::default();
+        for pixel in pixels {
+            if count == dimensions {
+                return Err(mismatch());
+            }
+            let (x, y) = (count % width, count / width);
+            packed[y * bytes_per_line + x / 8] |= u8::from(pixel) << (x % 8);
+            count += 1;
+        }
+        if count != dimensions {
+            return Err(mismatch());
+        }
+
+        let crc32 = encoder
+            .options
+            .crc32
+            .then(|| Crc32::checksum(packed.iter().map(|&b| encoder.options.bit_order.convert(b))));
+        let rows = packed.chunks(bytes_per_line).map(|bytes| {
+            (0..width)
+                .map(|x| (bytes[x / 8] >> (x % 8)) & 1)
+                .collect::<Vec<_>>()
+        });
+        encoder.write_image(name, width, height, x_hot, y_hot, crc32, &packed, rows)
+    }
+
+    /// Sets whether to write the index of each element of the array as a
+    /// comment after it.
+    ///
+    /// The index is zero-based and written as `/* [<index>] */`. This allows
+    /// tools to refer to the elements by index. The comments are ignored when
+    /// decoding. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).index_comments(true);
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert!(String::from_utf8(buf)
+    ///     .unwrap()
+    ///     .contains("0x00, /* [0] */ 0x1C, /* [1] */ 0x24, /* [2] */"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn index_comments(mut self, emit: bool) -> Self {
+        self.options.index_comments = emit;
+        self
+    }
+
+    /// Sets the comment to write before the `#define` directives.
+    ///
+    /// The comment is written verbatim followed by a newline, so it should
+    /// include the comment delimiters, such as `/* ... */`. This is useful for
+    /// preserving a license header read by [`Decoder::leading_comment`]. By
+    /// default, no comment is written.
+    ///
+    /// [`Decoder::leading_comment`]: crate::Decoder::leading_comment
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).leading_comment("/* comment */");
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert!(String::from_utf8(buf)
+    ///     .unwrap()
+    ///     .starts_with("/* comment */\n#define image_width 8\n"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn leading_comment(mut self, comment: impl Into<String>) -> Self {
+        self.options.leading_comment = Some(comment.into());
+        self
+    }
+
+    /// Sets the comment to write on its own line before the array
+    /// declaration.
+    ///
+    /// The comment is enclosed in `/*` and `*/`, so `comment` should not
+    /// include them. By default, no comment is written.
+    ///
+    /// Encoding returns an error if `comment` contains either `*/` or a
+    /// newline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).array_comment("8x7 icon");
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert!(String::from_utf8(buf)
+    ///     .unwrap()
+    ///     .contains("/* 8x7 icon */\nstatic unsigned char image_bits[] = {\n"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn array_comment(mut self, comment: impl Into<String>) -> Self {
+        self.options.array_comment = Some(comment.into());
+        self
+    }
+
+    /// Sets the string to indent each line of the array with.
+    ///
+    /// The default is four spaces. Encoding returns an error if `indent`
+    /// contains characters other than spaces and tabs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).indent("\t");
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert!(String::from_utf8(buf)
+    ///     .unwrap()
+    ///     .contains("{\n\t0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,\n};"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.options.indent = Some(indent.into());
+        self
+    }
+
+    /// Sets whether to write the opening brace of the array on the line
+    /// following the array declaration.
+    ///
+    /// This matches the style which puts the brace on its own line, such as
+    /// some configurations of clang-format. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).brace_on_next_line(true);
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert!(String::from_utf8(buf)
+    ///     .unwrap()
+    ///     .contains("static unsigned char image_bits[] =\n{\n"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn brace_on_next_line(mut self, enable: bool) -> Self {
+        self.options.brace_on_next_line = enable;
+        self
+    }
+
+    /// Sets whether to write the width padded to a multiple of 8 as
+    /// `#define <name>_width <value>`.
+    ///
+    /// `buf` still has the unpadded width, and the extra columns are encoded
+    /// as white pixels. This is useful for consumers which expect the width to
+    /// be byte-aligned. The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// let pixels = b"\x01\x00\x01\x00\x01\x00";
+    ///
+    /// let mut buf = Vec::new();
+    /// let encoder = Encoder::new(&mut buf).pad_width_to_byte(true);
+    /// encoder.encode(pixels, "image", 3, 2, None, None).unwrap();
+    /// let image = String::from_utf8(buf).unwrap();
+    /// assert!(image.starts_with("#define image_width 8\n"));
+    /// assert!(image.contains("0x05, 0x02,"));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn pad_width_to_byte(mut self, pad: bool) -> Self {
+        self.options.pad_width_to_byte = pad;
+        self
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Begins encoding the binary image row by row.
+    ///
+    /// This writes the header immediately and returns a [`RowEncoder`], which
+    /// accepts the rows of the image incrementally. See [`Encoder::encode`] for
+    /// `name`, `x_hot`, and `y_hot`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `name` is not a valid C identifier.
+    /// - Either `width` or `height` is `0`.
+    /// - Only one of `x_hot` and `y_hot` is [`Some`].
+    /// - Writing the CRC-32 checksum is enabled, since it cannot be written
+    ///   before all rows are known.
+    /// - An error occurs during I/O operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
+    ///
+    /// let mut buf = [u8::default(); 132];
+    /// let encoder = Encoder::new(buf.as_mut_slice());
+    /// let mut encoder = encoder.begin("image", 8, 7, None, None).unwrap();
+    /// for row in pixels.chunks(8) {
+    ///     encoder.write_row(row).unwrap();
+    /// }
+    /// encoder.finish().unwrap();
+    /// assert_eq!(buf, *include_bytes!("../tests/data/basic.xbm"));
+    /// ```
+    pub fn begin(
+        mut self,
+        name: impl AsRef<str>,
+        width: u32,
+        height: u32,
+        x_hot: Option<u32>,
+        y_hot: Option<u32>,
+    ) -> Result<RowEncoder<W>, Error> {
+        let name = name.as_ref();
+        let width = usize::try_from(width).expect("width should be in the range of `usize`");
+        let height = usize::try_from(height).expect("height should be in the range of `usize`");
+        validate_header(name, width, height, x_hot, y_hot)?;
+        validate_array_comment(self.options.array_comment.as_deref())?;
+        validate_indent(self.options.indent.as_deref())?;
+        if self.options.crc32 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "the CRC-32 checksum cannot be written row by row",
+            ));
+        }
+
+        self.write_header(name, width, height, x_hot, y_hot, None)?;
+        self.write_declaration(&format!("{name}_bits"))?;
+        let bytes_per_line = self.bytes_per_line(width);
+        Ok(RowEncoder {
+            encoder: self,
+            width,
+            height,
+            bytes_per_line,
+            rows: usize::default(),
+            pending: Vec::new(),
+        })
+    }
+
+    #[cfg(feature = "image")]
+    /// Encodes the grayscale image `image`.
+    ///
+    /// Pixels with a value less than `128` are encoded as black pixels, and
+    /// the others are encoded as white pixels.
+    ///
+    /// See [`Encoder::encode`] for `name`, `x_hot`, and `y_hot`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `name` is not a valid C identifier.
+    /// - Either the width or the height of `image` is `0`.
+    /// - Only one of `x_hot` and `y_hot` is [`Some`].
+    /// - An error occurs during I/O operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// let image = image::open("tests/data/qr_code.png")
+    ///     .map(|i| i.into_luma8())
+    ///     .unwrap();
+    ///
+    /// let mut buf = Vec::with_capacity(69454);
+    /// let encoder = Encoder::new(&mut buf);
+    /// encoder
+    ///     .encode_gray_image(&image, "image", None, None)
+    ///     .unwrap();
+    /// assert_eq!(buf, include_bytes!("../tests/data/qr_code.xbm"));
+    /// ```
+    pub fn encode_gray_image(
+        self,
+        image: &image::GrayImage,
+        name: impl AsRef<str>,
+        x_hot: Option<u32>,
+        y_hot: Option<u32>,
+    ) -> Result<(), Error> {
+        let buf = image
+            .as_raw()
+            .iter()
+            .map(|&p| u8::from(p <= (u8::MAX / 2)))
+            .collect::<Vec<_>>();
+        self.encode(buf, name, image.width(), image.height(), x_hot, y_hot)
     }
 
-    /// Encodes the binary image `buf`.
+    #[allow(clippy::missing_panics_doc)]
+    /// Returns the exact number of bytes which this encoder writes for the
+    /// image with the given header.
     ///
-    /// `0` represents a white pixel and `1` represents a black pixel.
+    /// This takes all options of the encoder into account, so this is useful
+    /// for presizing the output buffer. The length is computed from the
+    /// header without writing the array.
     ///
-    /// `name` accepts a string which follow the specification in [Unicode
-    /// Standard Annex #31], but it is recommended that `name` be restricted to
-    /// the ASCII subset of `XID_Start` and `XID_Continue`.
+    /// Returns [`None`] if the length overflows [`usize`].
     ///
-    /// `width` should be a multiple of 8.
+    /// # Examples
     ///
-    /// # Errors
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// // "B" (8x7)
+    /// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x01\x00\x00\x01\x00\x00\
+    ///                \x00\x00\x01\x01\x01\x00\x00\x00\
+    ///                \x00\x00\x00\x00\x00\x00\x00\x00";
     ///
-    /// Returns [`Err`] if an error occurs during I/O operations.
+    /// let encoder = Encoder::new(Vec::new());
+    /// let len = encoder.estimate_len("image", 8, 7, None, None).unwrap();
+    /// assert_eq!(len, 132);
     ///
-    /// # Panics
+    /// let mut buf = Vec::with_capacity(len);
+    /// let encoder = Encoder::new(&mut buf);
+    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    /// assert_eq!(buf.len(), len);
+    /// ```
+    pub fn estimate_len(
+        &self,
+        name: &str,
+        width: u32,
+        height: u32,
+        x_hot: Option<u32>,
+        y_hot: Option<u32>,
+    ) -> Option<usize> {
+        let width = usize::try_from(width).ok()?;
+        let height = usize::try_from(height).ok()?;
+
+        let mut encoder = Encoder {
+            writer: ByteCounter::new(io::sink()),
+            options: self.options.clone(),
+            index: 0,
+        };
+        encoder
+            .write_header(
+                name,
+                width,
+                height,
+                x_hot,
+                y_hot,
+                self.options.crc32.then_some(u32::default()),
+            )
+            .and_then(|()| encoder.write_declaration(&format!("{name}_bits")))
+            .and_then(|()| encoder.write_termination())
+            .expect("counting the bytes should not fail");
+
+        let element_size = self.options.element_type.size();
+        let elements_per_row = (width + element_size * 8 - 1) / (element_size * 8);
+        let elements = elements_per_row.checked_mul(height)?;
+        let lines = if self.options.visual_grid || self.options.wrap_per_row {
+            height
+        } else {
+            let elements_per_line = self.options.element_type.elements_per_line();
+            (elements + elements_per_line - 1) / elements_per_line
+        };
+        // "0x" and 2 hex digits per byte.
+        let literal_len = 2 + element_size * 2;
+        let elements_len = if self.options.index_comments {
+            // "<element>, /* [<index>] */" separated by a space, and a line break.
+            elements
+                .checked_mul(literal_len + 11)?
+                .checked_add(index_digits(elements)?)?
+        } else {
+            // "<element>," separated by a space, and a line break.
+            elements.checked_mul(literal_len + 2)?
+        };
+        let indent_len = self.options.indent.as_deref().map_or(4, str::len);
+        let mut len = lines
+            .checked_mul(indent_len)?
+            .checked_add(elements_len)?
+            .checked_add(encoder.writer.count)?;
+        if self.options.visual_grid {
+            let grid_len = if self.options.pad_width_to_byte {
+                (width + 7) / 8 * 8
+            } else {
+                width
+            };
+            // " /* <grid> */" after each row.
+            len = height.checked_mul(grid_len + 7)?.checked_add(len)?;
+        }
+        Some(len)
+    }
+
+    /// Creates a new `Encoder` which borrows the writer, keeping the options.
     ///
-    /// Panics if the length of `buf` and the image dimensions (the width
-    /// multiplied by the height) are different.
+    /// Since the encoding methods consume the `Encoder`, this is useful for
+    /// writing multiple images to the same writer with a single `Encoder`.
     ///
     /// # Examples
     ///
@@ -61,114 +1224,348 @@ impl<W: Write> Encoder<W> {
     ///                \x00\x00\x01\x01\x01\x00\x00\x00\
     ///                \x00\x00\x00\x00\x00\x00\x00\x00";
     ///
-    /// let mut buf = [u8::default(); 132];
-    /// let encoder = Encoder::new(buf.as_mut_slice());
-    /// encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
-    /// assert_eq!(buf, *include_bytes!("../tests/data/basic.xbm"));
+    /// let mut encoder = Encoder::new(Vec::new()).emit_crc32(true);
+    /// encoder
+    ///     .by_ref()
+    ///     .encode(pixels, "frame0", 8, 7, None, None)
+    ///     .unwrap();
+    /// encoder
+    ///     .by_ref()
+    ///     .encode(pixels, "frame1", 8, 7, None, None)
+    ///     .unwrap();
+    /// let image = String::from_utf8(encoder.into_inner()).unwrap();
+    /// assert_eq!(image.matches("_crc32").count(), 2);
     /// ```
+    #[inline]
+    pub fn by_ref(&mut self) -> Encoder<&mut W> {
+        Encoder {
+            writer: &mut self.writer,
+            options: self.options.clone(),
+            index: 0,
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
     ///
-    /// [Unicode Standard Annex #31]: https://www.unicode.org/reports/tr31/
-    pub fn encode(
-        self,
-        buf: impl AsRef<[u8]>,
-        name: impl AsRef<str>,
-        width: u32,
-        height: u32,
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// let encoder = Encoder::new(Vec::<u8>::new());
+    /// assert!(encoder.get_ref().is_empty());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Write;
+    /// #
+    /// # use xbm::Encoder;
+    /// #
+    /// let mut encoder = Encoder::new(Vec::new());
+    /// writeln!(encoder.get_mut(), "/* comment */").unwrap();
+    /// assert_eq!(encoder.get_ref(), b"/* comment */\n");
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consumes the `Encoder`, returning the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Encoder;
+    /// #
+    /// let encoder = Encoder::new(Vec::<u8>::new());
+    /// assert!(encoder.into_inner().is_empty());
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Replaces the writer with the one returned by `f`, keeping the options.
+    fn map_writer<V: Write>(self, f: impl FnOnce(W) -> V) -> Encoder<V> {
+        Encoder {
+            writer: f(self.writer),
+            options: self.options,
+            index: self.index,
+        }
+    }
+
+    /// Returns the number of the packed bytes per row of the image, including
+    /// the padding to a multiple of the element size.
+    const fn bytes_per_line(&self, width: usize) -> usize {
+        let element_bits = self.options.element_type.size() * 8;
+        ((width + element_bits - 1) / element_bits) * self.options.element_type.size()
+    }
+
+    /// Returns the number of the packed bytes per line of the array when each
+    /// line does not represent a row.
+    const fn bytes_per_chunk(&self) -> usize {
+        self.options.element_type.size() * self.options.element_type.elements_per_line()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Writes the whole image with the packed bytes `packed`.
+    ///
+    /// `rows` yields the pixels of each row, which are used for the visual
+    /// grid.
+    fn write_image(
+        &mut self,
+        name: &str,
+        width: usize,
+        height: usize,
         x_hot: Option<u32>,
         y_hot: Option<u32>,
+        crc32: Option<u32>,
+        packed: &[u8],
+        rows: impl Iterator<Item = impl AsRef<[u8]>>,
     ) -> Result<(), Error> {
-        let inner = |mut encoder: Self,
-                     buf: &[u8],
-                     name: &str,
-                     width: u32,
-                     height: u32,
-                     x_hot: Option<u32>,
-                     y_hot: Option<u32>|
-         -> Result<(), Error> {
-            let width = usize::try_from(width).expect("width should be in the range of `usize`");
-            let dimensions = usize::try_from(height).map(|h| width * h);
-            assert_eq!(
-                Ok(buf.len()),
-                dimensions,
-                "`buf` and the image dimensions are different"
-            );
-
-            if buf.iter().any(|&p| p > 1) {
-                return Err(Error::new(
-                    ErrorKind::InvalidData,
-                    "`buf` contains values other than `0` and `1`",
-                ));
+        self.write_header(name, width, height, x_hot, y_hot, crc32)?;
+        self.write_declaration(&format!("{name}_bits"))?;
+        if self.options.visual_grid {
+            for (elements_chunk, row) in packed.chunks(self.bytes_per_line(width)).zip(rows) {
+                self.write_elements(elements_chunk, Some(row.as_ref()))?;
             }
-
-            let mut chars = name.chars();
-            if !chars.next().is_some_and(unicode_ident::is_xid_start)
-                || !chars.all(unicode_ident::is_xid_continue)
-            {
-                return Err(Error::new(
-                    ErrorKind::InvalidData,
-                    "invalid C identifier prefix",
-                ));
+        } else if self.options.wrap_per_row {
+            for elements_chunk in packed.chunks(self.bytes_per_line(width)) {
+                self.write_elements(elements_chunk, None)?;
             }
-
-            if x_hot.is_some() != y_hot.is_some() {
-                return Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    "only one of `x_hot` and `y_hot` is `Some`",
-                ));
+        } else {
+            for elements_chunk in packed.chunks(self.bytes_per_chunk()) {
+                self.write_elements(elements_chunk, None)?;
             }
+        }
+        self.write_termination()
+    }
 
-            writeln!(encoder.writer, "#define {name}_width {width}")?;
-            writeln!(encoder.writer, "#define {name}_height {height}")?;
-            if let Some(pos) = x_hot {
-                writeln!(encoder.writer, "#define {name}_x_hot {pos}")?;
-            }
-            if let Some(pos) = y_hot {
-                writeln!(encoder.writer, "#define {name}_y_hot {pos}")?;
-            }
+    /// Writes the header.
+    fn write_header(
+        &mut self,
+        name: &str,
+        width: usize,
+        height: usize,
+        x_hot: Option<u32>,
+        y_hot: Option<u32>,
+        crc32: Option<u32>,
+    ) -> Result<(), Error> {
+        let width = if self.options.pad_width_to_byte {
+            (width + 7) / 8 * 8
+        } else {
+            width
+        };
+        if let Some(comment) = &self.options.leading_comment {
+            writeln!(self.writer, "{comment}")?;
+        }
+        writeln!(self.writer, "#define {name}_width {width}")?;
+        writeln!(self.writer, "#define {name}_height {height}")?;
+        if let Some(pos) = x_hot {
+            writeln!(self.writer, "#define {name}_x_hot {pos}")?;
+        }
+        if let Some(pos) = y_hot {
+            writeln!(self.writer, "#define {name}_y_hot {pos}")?;
+        }
+        if let Some(crc) = crc32 {
+            writeln!(self.writer, "#define {name}_crc32 {crc:#010X}")?;
+        }
 
-            writeln!(encoder.writer, "static unsigned char {name}_bits[] = {{")?;
-            let mut pixels_chunk = Vec::with_capacity(12);
-            for per_line in buf.chunks(width) {
-                for chunk in per_line.chunks(8) {
-                    let mut pixels = u8::default();
-                    for (i, pixel) in chunk.iter().enumerate() {
-                        pixels |= pixel << i;
-                    }
-                    pixels_chunk.push(pixels);
-                    if pixels_chunk.len() == 12 {
-                        let line = pixels_chunk
-                            .iter()
-                            .map(|p| format!("{p:#04X}"))
-                            .collect::<Vec<_>>()
-                            .join(", ");
-                        writeln!(encoder.writer, "    {line},")?;
-                        pixels_chunk.clear();
-                    }
-                }
+        if self.options.portable_types {
+            let element_type = self.options.element_type;
+            let c_type = element_type.as_stdint_type();
+            writeln!(
+                self.writer,
+                "#if defined(__STDC_VERSION__) && __STDC_VERSION__ >= 199901L"
+            )?;
+            writeln!(self.writer, "#include <stdint.h>")?;
+            writeln!(
+                self.writer,
+                "#elif !defined({})",
+                element_type.as_limit_macro()
+            )?;
+            writeln!(
+                self.writer,
+                "typedef {} {c_type};",
+                element_type.as_fallback_type()
+            )?;
+            writeln!(self.writer, "#endif")?;
+        }
+        if let Some(comment) = &self.options.array_comment {
+            writeln!(self.writer, "/* {comment} */")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the declaration of the array `identifier`.
+    fn write_declaration(&mut self, identifier: &str) -> Result<(), Error> {
+        let c_type = if self.options.portable_types {
+            self.options.element_type.as_stdint_type()
+        } else {
+            self.options.element_type.as_c_type()
+        };
+        if self.options.brace_on_next_line {
+            writeln!(self.writer, "static {c_type} {identifier}[] =\n{{")
+        } else {
+            writeln!(self.writer, "static {c_type} {identifier}[] = {{")
+        }
+    }
+
+    /// Writes a line of the array, followed by the visual grid of `row` if it
+    /// is [`Some`].
+    fn write_elements(&mut self, bytes: &[u8], row: Option<&[u8]>) -> Result<(), Error> {
+        let (element_type, bit_order) = (self.options.element_type, self.options.bit_order);
+        let elements = bytes
+            .chunks(element_type.size())
+            .map(|b| element_type.format(b, bit_order));
+        let line = if self.options.index_comments {
+            let start = self.index;
+            elements
+                .enumerate()
+                .map(|(i, e)| format!("{e}, /* [{}] */", start + i))
+                .collect::<Vec<_>>()
+                .join(" ")
+        } else {
+            let mut line = elements.collect::<Vec<_>>().join(", ");
+            line.push(',');
+            line
+        };
+        self.index += (bytes.len() + element_type.size() - 1) / element_type.size();
+        let indent = self.options.indent.as_deref().unwrap_or("    ");
+        write!(self.writer, "{indent}{line}")?;
+        if let Some(row) = row {
+            let mut grid = row
+                .iter()
+                .map(|&p| if p == 1 { '#' } else { '.' })
+                .collect::<String>();
+            if self.options.pad_width_to_byte {
+                let padding = (8 - row.len() % 8) % 8;
+                grid.extend(iter::repeat('.').take(padding));
             }
-            if !pixels_chunk.is_empty() {
-                let line = pixels_chunk
-                    .into_iter()
-                    .map(|p| format!("{p:#04X}"))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                writeln!(encoder.writer, "    {line},")?;
+            write!(self.writer, " /* {grid} */")?;
+        }
+        writeln!(self.writer)
+    }
+
+    /// Writes the image termination string and flushes the writer.
+    fn write_termination(&mut self) -> Result<(), Error> {
+        write!(self.writer, "}};")?;
+        if self.options.trailing_newline {
+            writeln!(self.writer)?;
+        }
+        self.writer.flush()
+    }
+}
+
+/// Encoder for XBM images which accepts the rows of the image incrementally.
+///
+/// This is created by [`Encoder::begin`].
+#[derive(Debug)]
+pub struct RowEncoder<W: Write> {
+    encoder: Encoder<W>,
+    width: usize,
+    height: usize,
+    bytes_per_line: usize,
+    rows: usize,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> RowEncoder<W> {
+    /// Encodes the next row of the binary image.
+    ///
+    /// `0` represents a white pixel and `1` represents a black pixel.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - The length of `row` and the width are different.
+    /// - `row` contains values other than `0` and `1`.
+    /// - All rows have already been written.
+    /// - An error occurs during I/O operations.
+    pub fn write_row(&mut self, row: impl AsRef<[u8]>) -> Result<(), Error> {
+        let row = row.as_ref();
+        if row.len() != self.width {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "`row` and the width are different",
+            ));
+        }
+        if row.iter().any(|&p| p > 1) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "`row` contains values other than `0` and `1`",
+            ));
+        }
+        if self.rows >= self.height {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "all rows have already been written",
+            ));
+        }
+
+        pack_row(row, self.bytes_per_line, &mut self.pending);
+        self.rows += 1;
+        if self.encoder.options.visual_grid || self.encoder.options.wrap_per_row {
+            let row = self.encoder.options.visual_grid.then_some(row);
+            self.encoder.write_elements(&self.pending, row)?;
+            self.pending.clear();
+        } else {
+            let bytes_per_chunk = self.encoder.bytes_per_chunk();
+            let len = self.pending.len() - (self.pending.len() % bytes_per_chunk);
+            for elements_chunk in self.pending[..len].chunks(bytes_per_chunk) {
+                self.encoder.write_elements(elements_chunk, None)?;
             }
-            writeln!(encoder.writer, "}};")
-        };
-        inner(
-            self,
-            buf.as_ref(),
-            name.as_ref(),
-            width,
-            height,
-            x_hot,
-            y_hot,
-        )
+            self.pending.drain(..len);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of the rows which have been written.
+    #[must_use]
+    #[inline]
+    pub const fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Finishes encoding the image by writing the image termination string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - The number of the written rows and the height are different.
+    /// - An error occurs during I/O operations.
+    pub fn finish(mut self) -> Result<(), Error> {
+        if self.rows != self.height {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "the number of the written rows and the height are different",
+            ));
+        }
+
+        if !self.pending.is_empty() {
+            self.encoder.write_elements(&self.pending, None)?;
+        }
+        self.encoder.write_termination()
     }
 }
 
 #[cfg(feature = "image")]
+/// Encodes the image with the name `image` and without the hotspot.
+///
+/// The options of the `Encoder` are respected. If the width of the image is
+/// not a multiple of 8, the declared width is not byte-aligned unless
+/// [`Encoder::pad_width_to_byte`] is enabled.
 impl<W: Write> image::ImageEncoder for Encoder<W> {
     fn write_image(
         self,
@@ -178,10 +1575,26 @@ impl<W: Write> image::ImageEncoder for Encoder<W> {
         color_type: image::ExtendedColorType,
     ) -> image::ImageResult<()> {
         use image::{
-            error::{EncodingError, ImageFormatHint},
+            error::{EncodingError, ImageFormatHint, ParameterError, ParameterErrorKind},
             ExtendedColorType, ImageError,
         };
 
+        let channels = match color_type {
+            ExtendedColorType::L1 | ExtendedColorType::L8 => Some(1),
+            ExtendedColorType::La8 => Some(2),
+            _ => None,
+        };
+        if channels.is_some_and(|c| {
+            let len = u64::from(width)
+                .checked_mul(u64::from(height))
+                .and_then(|n| n.checked_mul(c));
+            len.is_none() || u64::try_from(buf.len()).ok() != len
+        }) {
+            return Err(ImageError::Parameter(ParameterError::from_kind(
+                ParameterErrorKind::DimensionMismatch,
+            )));
+        }
+
         let name = "image";
         match color_type {
             ExtendedColorType::L1 => self
@@ -189,11 +1602,20 @@ impl<W: Write> image::ImageEncoder for Encoder<W> {
                 .map_err(ImageError::IoError),
             ExtendedColorType::L8 => {
                 let mut buf = buf.to_vec();
+                #[allow(clippy::needless_for_each)]
                 buf.iter_mut()
                     .for_each(|p| *p = u8::from(*p <= (u8::MAX / 2)));
                 self.encode(buf, name, width, height, None, None)
                     .map_err(ImageError::IoError)
             }
+            ExtendedColorType::La8 => {
+                let buf = buf
+                    .chunks_exact(2)
+                    .map(|p| u8::from(p[0] <= (u8::MAX / 2) && p[1] > (u8::MAX / 2)))
+                    .collect::<Vec<_>>();
+                self.encode(buf, name, width, height, None, None)
+                    .map_err(ImageError::IoError)
+            }
             _ => Err(ImageError::Encoding(EncodingError::new(
                 ImageFormatHint::Name(String::from("XBM")),
                 format!("unsupported color type `{color_type:?}`"),
@@ -202,6 +1624,256 @@ impl<W: Write> image::ImageEncoder for Encoder<W> {
     }
 }
 
+/// Encodes the binary image `pixels` and writes it to the file at `path`.
+///
+/// This is a shorthand for [`Encoder::encode`] with the created file wrapped
+/// in a [`BufWriter`]. The file is created if it does not exist, and is
+/// truncated if it does.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the file cannot be created, or if [`Encoder::encode`]
+/// returns an error.
+///
+/// # Panics
+///
+/// Panics if the length of `pixels` and the image dimensions (the width
+/// multiplied by the height) are different.
+///
+/// # Examples
+///
+/// ```
+/// # use std::{env, fs};
+/// #
+/// // "B" (8x7)
+/// let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+///                \x00\x00\x01\x01\x01\x00\x00\x00\
+///                \x00\x00\x01\x00\x00\x01\x00\x00\
+///                \x00\x00\x01\x01\x01\x00\x00\x00\
+///                \x00\x00\x01\x00\x00\x01\x00\x00\
+///                \x00\x00\x01\x01\x01\x00\x00\x00\
+///                \x00\x00\x00\x00\x00\x00\x00\x00";
+///
+/// let path = env::temp_dir().join("xbm-write_to_path-doctest.xbm");
+/// xbm::encode::write_to_path(&path, pixels, "image", 8, 7, None, None).unwrap();
+/// assert_eq!(
+///     fs::read(&path).unwrap(),
+///     include_bytes!("../tests/data/basic.xbm")
+/// );
+/// # fs::remove_file(path).unwrap();
+/// ```
+pub fn write_to_path(
+    path: impl AsRef<Path>,
+    pixels: impl AsRef<[u8]>,
+    name: impl AsRef<str>,
+    width: u32,
+    height: u32,
+    x_hot: Option<u32>,
+    y_hot: Option<u32>,
+) -> io::Result<()> {
+    let mut writer = File::create(path).map(BufWriter::new)?;
+    Encoder::new(&mut writer).encode(pixels, name, width, height, x_hot, y_hot)?;
+    writer.flush()
+}
+
+/// Validates the comment written before the array declaration.
+fn validate_array_comment(comment: Option<&str>) -> Result<(), Error> {
+    if comment.is_some_and(|c| c.contains("*/") || c.contains('\n')) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "the array comment contains either `*/` or a newline",
+        ));
+    }
+    Ok(())
+}
+
+/// Validates the indentation of the lines of the array.
+fn validate_indent(indent: Option<&str>) -> Result<(), Error> {
+    if indent.is_some_and(|i| !i.chars().all(|c| c == ' ' || c == '\t')) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "the indentation contains characters other than spaces and tabs",
+        ));
+    }
+    Ok(())
+}
+
+/// Validates the name, the dimensions, and the hotspot of the image.
+fn validate_header(
+    name: &str,
+    width: usize,
+    height: usize,
+    x_hot: Option<u32>,
+    y_hot: Option<u32>,
+) -> Result<(), Error> {
+    let mut chars = name.chars();
+    if !chars.next().is_some_and(unicode_ident::is_xid_start)
+        || !chars.all(unicode_ident::is_xid_continue)
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "invalid C identifier prefix",
+        ));
+    }
+
+    if width == 0 || height == 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "either `width` or `height` is `0`",
+        ));
+    }
+
+    if x_hot.is_some() != y_hot.is_some() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "only one of `x_hot` and `y_hot` is `Some`",
+        ));
+    }
+    Ok(())
+}
+
+/// Packs the pixels of a row into `packed`, padding them to `bytes_per_line`
+/// bytes.
+fn pack_row(row: &[u8], bytes_per_line: usize, packed: &mut Vec<u8>) {
+    let start = packed.len();
+    for chunk in row.chunks(8) {
+        let mut pixels = u8::default();
+        for (i, pixel) in chunk.iter().enumerate() {
+            pixels |= pixel << i;
+        }
+        packed.push(pixels);
+    }
+    packed.resize(start + bytes_per_line, u8::default());
+}
+
+/// The type of the elements of the array.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum ElementType {
+    /// `unsigned char` (8-bit).
+    #[default]
+    U8,
+
+    /// `unsigned short` (16-bit).
+    U16,
+
+    /// `unsigned int` (32-bit).
+    U32,
+}
+
+impl ElementType {
+    const fn size(self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::U16 => 2,
+            Self::U32 => 4,
+        }
+    }
+
+    const fn as_c_type(self) -> &'static str {
+        match self {
+            Self::U8 => "unsigned char",
+            Self::U16 => "unsigned short",
+            Self::U32 => "unsigned int",
+        }
+    }
+
+    const fn as_stdint_type(self) -> &'static str {
+        match self {
+            Self::U8 => "uint8_t",
+            Self::U16 => "uint16_t",
+            Self::U32 => "uint32_t",
+        }
+    }
+
+    /// Returns the macro of `<stdint.h>` for the maximum value of the type.
+    const fn as_limit_macro(self) -> &'static str {
+        match self {
+            Self::U8 => "UINT8_MAX",
+            Self::U16 => "UINT16_MAX",
+            Self::U32 => "UINT32_MAX",
+        }
+    }
+
+    /// Returns the type defined as the type of `<stdint.h>` before C99.
+    ///
+    /// `unsigned long` is used for 32 bits, since `unsigned int` is only
+    /// guaranteed to be at least 16-bit.
+    const fn as_fallback_type(self) -> &'static str {
+        match self {
+            Self::U8 => "unsigned char",
+            Self::U16 => "unsigned short",
+            Self::U32 => "unsigned long",
+        }
+    }
+
+    /// Formats `bytes` as an element in little-endian order, with the bits of
+    /// each byte in `bit_order`.
+    ///
+    /// The element is written as an uppercase hexadecimal literal which is
+    /// zero-padded to the width of the type, without an integer suffix such as
+    /// `U`, since the literals fit in the element type.
+    fn format(self, bytes: &[u8], bit_order: BitOrder) -> String {
+        let element = bytes.iter().rev().fold(u32::default(), |acc, &b| {
+            (acc << 8) | u32::from(bit_order.convert(b))
+        });
+        match self {
+            Self::U8 => format!("{element:#04X}"),
+            Self::U16 => format!("{element:#06X}"),
+            Self::U32 => format!("{element:#010X}"),
+        }
+    }
+
+    const fn elements_per_line(self) -> usize {
+        match self {
+            Self::U8 => 12,
+            Self::U16 => 8,
+            Self::U32 => 6,
+        }
+    }
+}
+
+/// Returns the total number of the decimal digits of the indices less than
+/// `n`.
+fn index_digits(n: usize) -> Option<usize> {
+    let (mut total, mut lower, mut upper, mut digits) = (0_usize, 0, 10_usize, 1);
+    while lower < n {
+        total = (n.min(upper) - lower)
+            .checked_mul(digits)?
+            .checked_add(total)?;
+        lower = upper;
+        upper = upper.saturating_mul(10);
+        digits += 1;
+    }
+    Some(total)
+}
+
+/// A writer which counts the number of the bytes written to the inner writer.
+#[derive(Debug)]
+struct ByteCounter<W: Write> {
+    inner: W,
+    count: usize,
+}
+
+impl<W: Write> ByteCounter<W> {
+    const fn new(inner: W) -> Self {
+        Self { inner, count: 0 }
+    }
+}
+
+impl<W: Write> Write for ByteCounter<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.count += len;
+        Ok(len)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// The error type indicating that an error occurred during encoding.
 pub type Error = io::Error;
 
@@ -215,4 +1887,45 @@ mod tests {
     fn error_type() {
         assert_eq!(any::type_name::<Error>(), any::type_name::<io::Error>());
     }
+
+    #[test]
+    fn default_element_type() {
+        assert_eq!(ElementType::default(), ElementType::U8);
+    }
+
+    #[test]
+    fn format_element() {
+        assert_eq!(ElementType::U8.format(&[u8::MIN], BitOrder::Lsb), "0x00");
+        assert_eq!(ElementType::U8.format(&[0x0A], BitOrder::Lsb), "0x0A");
+        assert_eq!(ElementType::U8.format(&[u8::MAX], BitOrder::Lsb), "0xFF");
+        assert_eq!(
+            ElementType::U16.format(&[u8::MIN; 2], BitOrder::Lsb),
+            "0x0000"
+        );
+        assert_eq!(
+            ElementType::U16.format(&[0x01, 0x02], BitOrder::Lsb),
+            "0x0201"
+        );
+        assert_eq!(
+            ElementType::U16.format(&[u8::MAX; 2], BitOrder::Lsb),
+            "0xFFFF"
+        );
+        assert_eq!(
+            ElementType::U32.format(&[u8::MIN; 4], BitOrder::Lsb),
+            "0x00000000"
+        );
+        assert_eq!(
+            ElementType::U32.format(&[0x01, 0x02, 0x03, 0x04], BitOrder::Lsb),
+            "0x04030201"
+        );
+        assert_eq!(
+            ElementType::U32.format(&[u8::MAX; 4], BitOrder::Lsb),
+            "0xFFFFFFFF"
+        );
+        assert_eq!(ElementType::U8.format(&[0x1C], BitOrder::Msb), "0x38");
+        assert_eq!(
+            ElementType::U16.format(&[0x01, 0x02], BitOrder::Msb),
+            "0x4080"
+        );
+    }
 }
@@ -4,7 +4,9 @@
 
 //! The `xbm` crate is a [XBM] encoding and decoding library.
 //!
-//! This crate supports the [X version 11 bitmap file format].
+//! This crate supports the [X version 11 bitmap file format]. The decoder
+//! also detects and decodes the X version 10 bitmap file format, whose array
+//! is declared as `short` instead of `char`.
 //!
 //! The width and the height of XBM are unlimited, but in this crate they are
 //! limited to [`u32`].
@@ -118,10 +120,269 @@
 // Lint levels of rustc.
 #![deny(missing_docs)]
 
+mod crc32;
 pub mod decode;
 pub mod encode;
+mod lint;
+
+use std::io::{BufRead, Cursor, Seek, Write};
 
 #[cfg(feature = "image")]
 pub use image;
 
-pub use crate::{decode::Decoder, encode::Encoder};
+pub use crate::{
+    decode::Decoder,
+    encode::Encoder,
+    lint::{lint, Lint},
+};
+
+/// Decodes the XBM image from `reader` and encodes it to `writer`.
+///
+/// The name of the image, the image dimensions, the hotspot, the presence of
+/// the CRC-32 checksum, and the comment before the `#define` directives are
+/// preserved.
+///
+/// # Errors
+///
+/// Returns [`Err`] if an error occurs during decoding or encoding.
+///
+/// # Examples
+///
+/// ```
+/// # use std::{fs::File, io::BufReader};
+/// #
+/// let reader = File::open("tests/data/hotspot.xbm")
+///     .map(BufReader::new)
+///     .unwrap();
+/// let mut buf = Vec::with_capacity(176);
+/// xbm::transcode(reader, &mut buf).unwrap();
+/// assert_eq!(buf, include_bytes!("../tests/data/hotspot.xbm"));
+/// ```
+pub fn transcode(reader: impl BufRead + Seek, writer: impl Write) -> Result<(), decode::Error> {
+    let decoder = Decoder::new(reader)?;
+    let header = decoder.metadata();
+    let comment = decoder.leading_comment().map(str::to_owned);
+    let buf = decoder.decode_to_vec()?;
+    let mut encoder = Encoder::new(writer).emit_crc32(header.crc32().is_some());
+    if let Some(comment) = comment {
+        encoder = encoder.leading_comment(comment);
+    }
+    encoder.encode(
+        buf,
+        header.name(),
+        header.width(),
+        header.height(),
+        header.x_hot(),
+        header.y_hot(),
+    )?;
+    Ok(())
+}
+
+/// Returns `true` if `buf` looks like the beginning of an XBM image.
+///
+/// This checks whether `buf`, after an optional byte order mark, whitespace
+/// and comments, begins with a `#define` directive whose identifier ends with
+/// `_width`. Since XBM has no magic number, this is only a heuristic: a C
+/// source file which happens to start with such a directive is also accepted,
+/// and `buf` must contain at least the whole identifier of the first
+/// directive. Use [`Decoder::new`] to find out whether the image is actually
+/// valid.
+///
+/// # Examples
+///
+/// ```
+/// assert!(xbm::guess_is_xbm(include_bytes!("../tests/data/basic.xbm")));
+/// assert!(xbm::guess_is_xbm(b"/* icon */\n#define icon_width 8\n"));
+/// assert!(!xbm::guess_is_xbm(b"P1\n8 7\n"));
+/// ```
+#[must_use]
+pub fn guess_is_xbm(buf: &[u8]) -> bool {
+    fn trim_start(buf: &[u8]) -> &[u8] {
+        let start = buf
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(buf.len());
+        &buf[start..]
+    }
+
+    let mut rest = buf.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(buf);
+    loop {
+        rest = trim_start(rest);
+        if let Some(comment) = rest.strip_prefix(b"/*") {
+            let Some(end) = comment.windows(2).position(|w| w == b"*/") else {
+                return false;
+            };
+            rest = &comment[end + 2..];
+        } else if let Some(comment) = rest.strip_prefix(b"//") {
+            let end = comment
+                .iter()
+                .position(|&b| b == b'\n')
+                .unwrap_or(comment.len());
+            rest = &comment[end..];
+        } else {
+            break;
+        }
+    }
+    let Some(rest) = rest
+        .strip_prefix(b"#")
+        .and_then(|r| trim_start(r).strip_prefix(b"define"))
+    else {
+        return false;
+    };
+    if !rest.first().is_some_and(u8::is_ascii_whitespace) {
+        return false;
+    }
+    let rest = trim_start(rest);
+    let end = rest
+        .iter()
+        .position(u8::is_ascii_whitespace)
+        .unwrap_or(rest.len());
+    rest[..end].len() > "_width".len() && rest[..end].ends_with(b"_width")
+}
+
+/// The order of the pixels within each byte of the array.
+///
+/// XBM defines that the least significant bit of each byte represents the
+/// leftmost pixel, but some tools write the most significant bit first.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum BitOrder {
+    /// The least significant bit is the leftmost pixel, as defined by XBM.
+    #[default]
+    Lsb,
+
+    /// The most significant bit is the leftmost pixel.
+    Msb,
+}
+
+impl BitOrder {
+    /// Converts `byte` between this order and [`BitOrder::Lsb`].
+    pub(crate) const fn convert(self, byte: u8) -> u8 {
+        match self {
+            Self::Lsb => byte,
+            Self::Msb => byte.reverse_bits(),
+        }
+    }
+}
+
+/// A decoded XBM image which owns its pixels.
+///
+/// This is created from the bytes of the XBM image with [`TryFrom`], and can
+/// be encoded back with [`Bitmap::to_xbm`].
+///
+/// # Examples
+///
+/// ```
+/// # use xbm::Bitmap;
+/// #
+/// let bytes = include_bytes!("../tests/data/basic.xbm");
+/// let bitmap = Bitmap::try_from(bytes.as_slice()).unwrap();
+/// assert_eq!(bitmap.header().width(), 8);
+/// assert_eq!(bitmap.header().height(), 7);
+/// assert_eq!(bitmap.pixels().len(), 56);
+/// assert_eq!(bitmap.to_xbm(), bytes);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Bitmap {
+    header: decode::Header,
+    pixels: Vec<u8>,
+}
+
+impl Bitmap {
+    /// Returns the header of the image.
+    #[must_use]
+    #[inline]
+    pub const fn header(&self) -> &decode::Header {
+        &self.header
+    }
+
+    /// Returns the pixels of the image.
+    ///
+    /// `0` represents a white pixel and `1` represents a black pixel.
+    #[must_use]
+    #[inline]
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Returns the pixels of the image, consuming the `Bitmap`.
+    #[must_use]
+    #[inline]
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// Returns `true` if `self` and `other` have the same image dimensions
+    /// and pixels.
+    ///
+    /// Unlike [`PartialEq`], this ignores the name of the image, the hotspot,
+    /// and the CRC-32 checksum.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use xbm::Bitmap;
+    /// #
+    /// let basic = include_bytes!("../tests/data/basic.xbm");
+    /// let basic = Bitmap::try_from(basic.as_slice()).unwrap();
+    /// let name = include_bytes!("../tests/data/name.xbm");
+    /// let name = Bitmap::try_from(name.as_slice()).unwrap();
+    /// assert_ne!(basic, name);
+    /// assert!(basic.pixels_eq(&name));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn pixels_eq(&self, other: &Self) -> bool {
+        self.header.width() == other.header.width()
+            && self.header.height() == other.header.height()
+            && self.pixels == other.pixels
+    }
+
+    #[allow(clippy::missing_panics_doc)]
+    /// Encodes the image into the XBM format.
+    ///
+    /// The name of the image, the hotspot, and the presence of the CRC-32
+    /// checksum are preserved.
+    #[must_use]
+    pub fn to_xbm(&self) -> Vec<u8> {
+        let header = &self.header;
+        let mut buf = Vec::new();
+        Encoder::new(&mut buf)
+            .emit_crc32(header.crc32().is_some())
+            .encode(
+                &self.pixels,
+                header.name(),
+                header.width(),
+                header.height(),
+                header.x_hot(),
+                header.y_hot(),
+            )
+            .expect("the decoded image should be valid");
+        buf
+    }
+
+    /// Decodes the image read from `reader`.
+    fn decode(reader: impl BufRead + Seek) -> Result<Self, decode::Error> {
+        let decoder = Decoder::new(reader)?;
+        let header = decoder.metadata();
+        let pixels = decoder.decode_to_vec()?;
+        Ok(Self { header, pixels })
+    }
+}
+
+impl TryFrom<&[u8]> for Bitmap {
+    type Error = decode::Error;
+
+    #[inline]
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::decode(Cursor::new(bytes))
+    }
+}
+
+impl TryFrom<Vec<u8>> for Bitmap {
+    type Error = decode::Error;
+
+    #[inline]
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::decode(Cursor::new(bytes))
+    }
+}
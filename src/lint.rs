@@ -0,0 +1,283 @@
+// SPDX-FileCopyrightText: 2024 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Checks XBM images for deviations from the specification.
+
+use std::{fmt, io::BufRead};
+
+use crate::decode::{self, Error};
+
+/// A deviation from the [X version 11 bitmap file format].
+///
+/// [X version 11 bitmap file format]: https://www.x.org/releases/X11R7.7/doc/libX11/libX11/libX11.html#Manipulating_Bitmaps
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Lint {
+    line: usize,
+    message: String,
+}
+
+impl Lint {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self { line, message }
+    }
+
+    /// Returns the one-based line number where the deviation was found.
+    #[must_use]
+    #[inline]
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the message describing the deviation.
+    #[must_use]
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Lint {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+/// Checks the XBM image read from `reader` and returns all deviations from
+/// the specification.
+///
+/// Unlike [`Decoder`](crate::Decoder), this parses the image leniently and
+/// does not stop at the first deviation. The deviations are returned in the
+/// order of the lines where they were found.
+///
+/// # Errors
+///
+/// Returns [`Err`] if an error occurs during I/O operations.
+///
+/// # Examples
+///
+/// ```
+/// # use std::{fs::File, io::BufReader};
+/// #
+/// let reader = File::open("tests/data/basic.xbm")
+///     .map(BufReader::new)
+///     .unwrap();
+/// assert!(xbm::lint(reader).unwrap().is_empty());
+///
+/// let reader = File::open("tests/data/without_unsigned.xbm")
+///     .map(BufReader::new)
+///     .unwrap();
+/// let lints = xbm::lint(reader).unwrap();
+/// assert_eq!(lints.len(), 1);
+/// assert_eq!(lints[0].line(), 3);
+/// ```
+pub fn lint(reader: impl BufRead) -> Result<Vec<Lint>, Error> {
+    let mut lints = Vec::new();
+    let mut name = Option::<String>::default();
+    let mut width = Option::default();
+    let mut height = Option::default();
+    let mut x_hot = Option::default();
+    let mut y_hot = Option::default();
+    let mut is_declared = false;
+    let mut awaits_brace = false;
+    let mut is_terminated = false;
+    let mut bytes = usize::default();
+    let mut last_line = usize::default();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = i + 1;
+        last_line = number;
+        let mut push = |message: String| lints.push(Lint::new(number, message));
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.contains("/*") || line.contains("//") {
+            push(String::from("comments are not part of the format"));
+        }
+        let code = decode::strip_comments(&line);
+        let code = code.trim();
+        if code.is_empty() {
+            continue;
+        }
+        if is_terminated {
+            push(String::from("unexpected content after the array"));
+            continue;
+        }
+
+        let mut body = None;
+        if is_declared {
+            body = Some(code);
+        } else if awaits_brace {
+            awaits_brace = false;
+            if let Some(rest) = code.strip_prefix('{') {
+                is_declared = true;
+                body = Some(rest);
+            } else {
+                push(String::from("missing `{` after the array declaration"));
+            }
+        } else if let Some(mut tokens) = decode::split_define(code) {
+            let Some(key) = tokens.next() else {
+                push(String::from("missing identifier in `#define`"));
+                continue;
+            };
+            let Some((prefix, suffix)) = ["width", "height", "x_hot", "y_hot"]
+                .into_iter()
+                .find_map(|s| Some((key.strip_suffix(s)?.strip_suffix('_')?, s)))
+            else {
+                push(format!("unknown identifier `{key}`"));
+                continue;
+            };
+            match &name {
+                Some(n) if n != prefix => {
+                    push(format!("inconsistent name `{prefix}` (expected `{n}`)"));
+                }
+                Some(_) => {}
+                None => {
+                    if !is_identifier(prefix) {
+                        push(format!("invalid C identifier `{prefix}`"));
+                    } else if !prefix.is_ascii() {
+                        push(format!("non-ASCII identifier `{prefix}`"));
+                    }
+                    name = Some(prefix.into());
+                }
+            }
+            let value = match tokens.next() {
+                Some(v) if v.bytes().all(|b| b.is_ascii_digit()) => v.parse().ok(),
+                Some(v) => {
+                    push(format!("value `{v}` is not a decimal integer"));
+                    decode::parse_value(v).ok()
+                }
+                None => {
+                    push(format!("missing value of `{key}`"));
+                    None
+                }
+            };
+            if tokens.next().is_some() {
+                push(format!("unexpected tokens after the value of `{key}`"));
+            }
+            let slot = match suffix {
+                "width" => &mut width,
+                "height" => {
+                    if width.is_none() {
+                        push(String::from("height is defined before the width"));
+                    }
+                    &mut height
+                }
+                "x_hot" => &mut x_hot,
+                _ => &mut y_hot,
+            };
+            if slot.is_some() {
+                push(format!("duplicate definition of `{key}`"));
+            }
+            *slot = Some(value);
+        } else if let Some((declaration, rest)) = code.split_once('{') {
+            is_declared = true;
+            body = Some(rest);
+
+            if let Some(declaration) = declaration.trim_end().strip_suffix('=') {
+                lint_declaration(declaration, name.as_deref(), &mut push);
+            } else {
+                push(String::from("missing `=` in the array declaration"));
+            }
+        } else if let Some(declaration) = code.strip_suffix('=') {
+            // The opening brace is on the next line.
+            awaits_brace = true;
+            lint_declaration(declaration, name.as_deref(), &mut push);
+        } else {
+            push(format!("unexpected line `{code}`"));
+        }
+
+        let Some(mut body) = body else {
+            continue;
+        };
+        if let Some((content, rest)) = body.split_once("};") {
+            is_terminated = true;
+            body = content;
+            if !rest.trim().is_empty() {
+                push(String::from("unexpected content after the array"));
+            }
+        }
+        for token in body.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            bytes += 1;
+            let digits = if let Some(digits) = token.strip_prefix("0x") {
+                digits
+            } else if let Some(digits) = token.strip_prefix("0X") {
+                push(format!("hex byte `{token}` uses the uppercase prefix `0X`"));
+                digits
+            } else {
+                push(format!(
+                    "hex byte `{token}` is not in the hexadecimal notation"
+                ));
+                continue;
+            };
+            if u8::from_str_radix(digits, 16).is_err() {
+                push(format!("invalid hex byte `{token}`"));
+            } else if digits.len() != 2 {
+                push(format!("hex byte `{token}` does not have two digits"));
+            }
+        }
+        if is_terminated {
+            if let (Some(Some(width)), Some(Some(height))) = (width, height) {
+                let expected = (u64::from(width) + 7) / 8 * u64::from(height);
+                if u64::try_from(bytes).ok() != Some(expected) {
+                    push(format!(
+                        "array has `{bytes}` bytes (expected `{expected}` bytes)"
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut push = |message: &str| lints.push(Lint::new(last_line, message));
+    if width.is_none() {
+        push("missing width");
+    }
+    if height.is_none() {
+        push("missing height");
+    }
+    if x_hot.is_some() != y_hot.is_some() {
+        push("only one of the hotspot coordinates is defined");
+    }
+    if !is_declared {
+        push("missing array declaration");
+    } else if !is_terminated {
+        push("array is not terminated by `};`");
+    }
+    Ok(lints)
+}
+
+/// Checks the array declaration before `=`.
+fn lint_declaration(declaration: &str, name: Option<&str>, mut push: impl FnMut(String)) {
+    let mut tokens = declaration.split_whitespace().collect::<Vec<_>>();
+    let identifier = tokens.pop().unwrap_or_default();
+    match tokens.as_slice() {
+        ["static", "unsigned", "char"] => {}
+        ["static", "char"] => push(String::from("array is declared as signed `char`")),
+        ["static", "unsigned", "short"] => push(String::from(
+            "array is declared as `unsigned short` (X10 format)",
+        )),
+        [.., "char"] => push(String::from("array is not declared as `static`")),
+        _ => push(String::from("invalid type of the array")),
+    }
+    match identifier
+        .strip_suffix("_bits[]")
+        .zip(name)
+        .map(|(p, n)| p == n)
+    {
+        Some(true) => {}
+        Some(false) => push(format!("inconsistent identifier `{identifier}`")),
+        None => push(format!("invalid identifier `{identifier}`")),
+    }
+}
+
+/// Returns `true` if `name` is a valid C identifier.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(unicode_ident::is_xid_start)
+        && chars.all(unicode_ident::is_xid_continue)
+}
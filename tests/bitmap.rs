@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2024 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+
+use xbm::{decode::Error, Bitmap};
+
+#[test]
+fn try_from_slice() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let bitmap = Bitmap::try_from(include_bytes!("data/hotspot.xbm").as_slice()).unwrap();
+    assert_eq!(bitmap.header().name(), "image");
+    assert_eq!(bitmap.header().width(), 8);
+    assert_eq!(bitmap.header().height(), 7);
+    assert_eq!(bitmap.header().hotspot(), Some((4, 3)));
+    assert_eq!(bitmap.pixels(), expected);
+    assert_eq!(bitmap.into_pixels(), expected);
+}
+
+#[test]
+fn try_from_vec() {
+    let bytes = fs::read("tests/data/basic.xbm").unwrap();
+    let bitmap = Bitmap::try_from(bytes).unwrap();
+    assert_eq!(bitmap.header().width(), 8);
+    assert_eq!(bitmap.header().height(), 7);
+    assert_eq!(bitmap.pixels().len(), 56);
+}
+
+#[test]
+fn try_from_invalid_image() {
+    let err = Bitmap::try_from(b"#define image_width 8\n".as_slice()).unwrap_err();
+    assert!(matches!(err, Error::InvalidHeader { .. }));
+}
+
+#[test]
+fn to_xbm() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/crc32.xbm",
+        "tests/data/hotspot.xbm",
+        "tests/data/name.xbm",
+        "tests/data/width_7.xbm",
+    ] {
+        let bytes = fs::read(path).unwrap();
+        let bitmap = Bitmap::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(bitmap.to_xbm(), bytes, "{path}");
+        assert_eq!(Bitmap::try_from(bitmap.to_xbm()).unwrap(), bitmap);
+    }
+}
+
+#[test]
+fn pixels_eq() {
+    let basic = Bitmap::try_from(fs::read("tests/data/basic.xbm").unwrap()).unwrap();
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/crc32.xbm",
+        "tests/data/hotspot.xbm",
+        "tests/data/name.xbm",
+    ] {
+        let bitmap = Bitmap::try_from(fs::read(path).unwrap()).unwrap();
+        assert!(basic.pixels_eq(&bitmap), "{path}");
+        assert!(bitmap.pixels_eq(&basic), "{path}");
+    }
+    assert_ne!(
+        basic,
+        Bitmap::try_from(fs::read("tests/data/name.xbm").unwrap()).unwrap()
+    );
+
+    // Different pixels with the same dimensions.
+    let image = b"#define image_width 8\n\
+                  #define image_height 7\n\
+                  static unsigned char image_bits[] = {\n\
+                  0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x01,\n\
+                  };\n";
+    let other = Bitmap::try_from(image.as_slice()).unwrap();
+    assert!(!basic.pixels_eq(&other));
+
+    // The same number of pixels with different dimensions.
+    let image = b"#define image_width 7\n\
+                  #define image_height 8\n\
+                  static unsigned char image_bits[] = {\n\
+                  0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0x00,\n\
+                  };\n";
+    let transposed = Bitmap::try_from(image.as_slice()).unwrap();
+    assert_eq!(transposed.pixels().len(), basic.pixels().len());
+    assert!(!basic.pixels_eq(&transposed));
+}
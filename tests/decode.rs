@@ -5,12 +5,16 @@
 use std::{
     error::Error as _,
     fs::File,
-    io::{BufReader, Cursor},
+    io::{BufRead, BufReader, Cursor},
     num::{IntErrorKind, ParseIntError},
+    thread,
 };
 
-use indoc::indoc;
-use xbm::{decode::Error, Decoder};
+use indoc::{formatdoc, indoc};
+use xbm::{
+    decode::{Error, Format, Limits},
+    BitOrder, Decoder,
+};
 
 #[test]
 fn decode() {
@@ -31,6 +35,7 @@ fn decode() {
         assert_eq!(decoder.name(), "image");
         assert_eq!(decoder.width(), 8);
         assert_eq!(decoder.height(), 7);
+        assert_eq!(decoder.dimensions(), (8, 7));
         assert_eq!(decoder.x_hot(), None);
         assert_eq!(decoder.y_hot(), None);
         let mut buf = [u8::default(); 56];
@@ -53,6 +58,91 @@ fn decode() {
     }
 }
 
+#[test]
+fn decode_single_line() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let reader = File::open("tests/data/basic_single_line.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let mut buf = [u8::default(); 56];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+    {
+        let reader = File::open("tests/data/basic_single_line.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let buf = decoder.decode_packed().unwrap();
+        assert_eq!(*buf, *b"\x00\x1C\x24\x1C\x24\x1C\x00");
+    }
+    {
+        let reader = File::open("tests/data/basic_single_line.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap().verify_row_length(true);
+        let err = decoder.decode_to_vec().unwrap_err();
+        assert!(err.is_row_length_mismatch());
+    }
+    #[cfg(feature = "rayon")]
+    {
+        let reader = File::open("tests/data/basic_single_line.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let mut buf = [u8::default(); 56];
+        decoder.decode_parallel(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+    for image in [
+        "#define image_width 8\n#define image_height 7\nstatic unsigned char image_bits[] = { 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, };",
+        "#define image_width 8\n#define image_height 7\nstatic unsigned char image_bits[] = {0x00,0x1C,0x24,0x1C,0x24,0x1C,0x00};  \n\n",
+    ] {
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        let mut buf = [u8::default(); 56];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+    {
+        let image = "#define image_width 8\n#define image_height 7\nstatic unsigned char image_bits[] = {0x00,0x1C,0x24,0x1C,0x24,0x1C,0x00}";
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        let err = decoder.decode_to_vec().unwrap_err();
+        assert!(err.is_invalid_termination());
+    }
+}
+
+#[test]
+fn decode_from_bytes() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let decoder = Decoder::from_bytes(include_bytes!("data/basic.xbm")).unwrap();
+    assert_eq!(decoder.width(), 8);
+    assert_eq!(decoder.height(), 7);
+    let mut buf = [u8::default(); 56];
+    decoder.decode(&mut buf).unwrap();
+    assert_eq!(buf, *expected);
+
+    let err = Decoder::from_bytes(b"").unwrap_err();
+    assert!(matches!(err, Error::InvalidHeader { .. }));
+}
+
 #[test]
 fn decode_lower_hex() {
     // "B" (8x7)
@@ -78,6 +168,31 @@ fn decode_lower_hex() {
     assert_eq!(buf, *expected);
 }
 
+#[test]
+fn decode_upper_prefix() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let reader = File::open("tests/data/basic_upper_prefix.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.name(), "image");
+    assert_eq!(decoder.width(), 8);
+    assert_eq!(decoder.height(), 7);
+    assert_eq!(decoder.x_hot(), None);
+    assert_eq!(decoder.y_hot(), None);
+    let mut buf = [u8::default(); 56];
+    decoder.decode(&mut buf).unwrap();
+    assert_eq!(buf, *expected);
+}
+
 #[test]
 fn decode_width_name() {
     // "B" (8x7)
@@ -103,6 +218,84 @@ fn decode_width_name() {
     assert_eq!(buf, *expected);
 }
 
+#[test]
+fn decode_with_name_containing_underscores_and_digits() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    for name in [
+        "big_icon_2",
+        "icon2",
+        "icon_width",
+        "icon_height",
+        "icon_x_hot",
+        "width_width",
+    ] {
+        let image = formatdoc! {"
+            #define {name}_width 8
+            #define {name}_height 7
+            #define {name}_x_hot 4
+            #define {name}_y_hot 3
+            static unsigned char {name}_bits[] = {{
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            }};
+        "};
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        assert_eq!(decoder.name(), name);
+        assert_eq!(decoder.dimensions(), (8, 7));
+        assert_eq!(decoder.hotspot(), Some((4, 3)));
+        assert!(decoder.extra_defines().is_empty());
+        assert_eq!(decoder.decode_to_vec().unwrap(), expected);
+    }
+}
+
+#[test]
+fn decode_bools() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/x10.xbm",
+    ] {
+        let decoder = Decoder::new(File::open(path).map(BufReader::new).unwrap()).unwrap();
+        let expected = decoder
+            .decode_to_vec()
+            .unwrap()
+            .into_iter()
+            .map(|p| p == 1)
+            .collect::<Vec<_>>();
+
+        let decoder = Decoder::new(File::open(path).map(BufReader::new).unwrap()).unwrap();
+        let mut buf = vec![bool::default(); expected.len()];
+        decoder.decode_bools(&mut buf).unwrap();
+        assert_eq!(buf, expected, "{path}");
+
+        let decoder = Decoder::new(File::open(path).map(BufReader::new).unwrap())
+            .unwrap()
+            .invert(true);
+        let mut buf = vec![bool::default(); expected.len()];
+        decoder.decode_bools(&mut buf).unwrap();
+        assert!(buf.iter().zip(&expected).all(|(a, b)| a != b), "{path}");
+    }
+}
+
+#[test]
+#[should_panic(expected = "`buf` and the image dimensions are different")]
+fn decode_bools_with_invalid_buffer() {
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    let mut buf = [bool::default(); 55];
+    let _ = decoder.decode_bools(&mut buf);
+}
+
 #[test]
 fn decode_to_vec() {
     // "B" (8x7)
@@ -124,1064 +317,3183 @@ fn decode_to_vec() {
 }
 
 #[test]
-fn decode_16x14() {
-    // "B" (16x14)
-    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
-
+fn decode_packed() {
     {
-        let reader = File::open("tests/data/16x14.xbm")
+        let reader = File::open("tests/data/basic.xbm")
             .map(BufReader::new)
             .unwrap();
         let decoder = Decoder::new(reader).unwrap();
-        assert_eq!(decoder.name(), "image");
-        assert_eq!(decoder.width(), 16);
-        assert_eq!(decoder.height(), 14);
-        assert_eq!(decoder.x_hot(), None);
-        assert_eq!(decoder.y_hot(), None);
-        let mut buf = [u8::default(); 224];
-        decoder.decode(&mut buf).unwrap();
-        assert_eq!(buf, *expected);
+        let buf = decoder.decode_packed().unwrap();
+        assert_eq!(*buf, *b"\x00\x1C\x24\x1C\x24\x1C\x00");
     }
     {
-        let reader = File::open("tests/data/16x14_minified.xbm")
+        let reader = File::open("tests/data/width_14.xbm")
             .map(BufReader::new)
             .unwrap();
         let decoder = Decoder::new(reader).unwrap();
-        assert_eq!(decoder.name(), "image");
-        assert_eq!(decoder.width(), 16);
-        assert_eq!(decoder.height(), 14);
-        assert_eq!(decoder.x_hot(), None);
-        assert_eq!(decoder.y_hot(), None);
-        let mut buf = [u8::default(); 224];
-        decoder.decode(&mut buf).unwrap();
-        assert_eq!(buf, *expected);
+        let buf = decoder.decode_packed().unwrap();
+        assert_eq!(buf.len(), 24);
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
+            };
+        "};
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let err = decoder.decode_packed().unwrap_err();
+        if let Error::InvalidImageSize {
+            expected_pixels,
+            decoded_pixels,
+        } = err
+        {
+            assert_eq!(expected_pixels, 56);
+            assert_eq!(decoded_pixels, 48);
+        } else {
+            unreachable!();
+        }
     }
 }
 
 #[test]
-fn decode_width_7() {
-    // "I" (7x6)
-    let expected = b"\x00\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x01\x01\x01\x00\x00\
-                     \x00\x00\x00\x01\x00\x00\x00\
-                     \x00\x00\x00\x01\x00\x00\x00\
-                     \x00\x00\x01\x01\x01\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x00";
-
-    {
-        let reader = File::open("tests/data/width_7.xbm")
-            .map(BufReader::new)
-            .unwrap();
+fn decode_into_vec() {
+    let mut buf = vec![u8::MAX; 1024];
+    let capacity = buf.capacity();
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
         let decoder = Decoder::new(reader).unwrap();
-        assert_eq!(decoder.name(), "image");
-        assert_eq!(decoder.width(), 7);
-        assert_eq!(decoder.height(), 6);
-        assert_eq!(decoder.x_hot(), None);
-        assert_eq!(decoder.y_hot(), None);
-        let mut buf = [u8::default(); 42];
-        decoder.decode(&mut buf).unwrap();
-        assert_eq!(buf, *expected);
-    }
-    {
-        let reader = File::open("tests/data/width_7_minified.xbm")
-            .map(BufReader::new)
-            .unwrap();
+        let expected = decoder.decode_to_vec().unwrap();
+
+        let reader = File::open(path).map(BufReader::new).unwrap();
         let decoder = Decoder::new(reader).unwrap();
-        assert_eq!(decoder.name(), "image");
-        assert_eq!(decoder.width(), 7);
-        assert_eq!(decoder.height(), 6);
-        assert_eq!(decoder.x_hot(), None);
-        assert_eq!(decoder.y_hot(), None);
-        let mut buf = [u8::default(); 42];
-        decoder.decode(&mut buf).unwrap();
-        assert_eq!(buf, *expected);
+        decoder.decode_into_vec(&mut buf).unwrap();
+        assert_eq!(buf, expected);
+        assert_eq!(buf.capacity(), capacity);
     }
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
+        };
+    "};
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    let err = decoder.decode_into_vec(&mut buf).unwrap_err();
+    assert!(err.is_invalid_image_size());
 }
 
 #[test]
-fn decode_width_14() {
-    // "I" (14x12)
-    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
-
-    {
-        let reader = File::open("tests/data/width_14.xbm")
-            .map(BufReader::new)
-            .unwrap();
+fn decode_packed_into() {
+    let mut buf = vec![u8::MAX; 1024];
+    let capacity = buf.capacity();
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
         let decoder = Decoder::new(reader).unwrap();
-        assert_eq!(decoder.name(), "image");
-        assert_eq!(decoder.width(), 14);
-        assert_eq!(decoder.height(), 12);
-        assert_eq!(decoder.x_hot(), None);
-        assert_eq!(decoder.y_hot(), None);
-        let mut buf = [u8::default(); 168];
-        decoder.decode(&mut buf).unwrap();
-        assert_eq!(buf, *expected);
-    }
-    {
-        let reader = File::open("tests/data/width_14_minified.xbm")
-            .map(BufReader::new)
-            .unwrap();
+        let expected = decoder.decode_packed().unwrap();
+
+        let reader = File::open(path).map(BufReader::new).unwrap();
         let decoder = Decoder::new(reader).unwrap();
-        assert_eq!(decoder.name(), "image");
-        assert_eq!(decoder.width(), 14);
-        assert_eq!(decoder.height(), 12);
-        assert_eq!(decoder.x_hot(), None);
-        assert_eq!(decoder.y_hot(), None);
-        let mut buf = [u8::default(); 168];
-        decoder.decode(&mut buf).unwrap();
+        decoder.decode_packed_into(&mut buf).unwrap();
         assert_eq!(buf, *expected);
+        assert_eq!(buf.capacity(), capacity);
     }
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
+        };
+    "};
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    let err = decoder.decode_packed_into(&mut buf).unwrap_err();
+    assert!(err.is_invalid_image_size());
 }
 
 #[test]
-fn decode_with_hotspot() {
-    // "B" (8x7)
-    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x01\x01\x01\x00\x00\x00\
-                     \x00\x00\x01\x00\x00\x01\x00\x00\
-                     \x00\x00\x01\x01\x01\x00\x00\x00\
-                     \x00\x00\x01\x00\x00\x01\x00\x00\
-                     \x00\x00\x01\x01\x01\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x00\x00";
+fn decode_both() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let (packed, pixels) = decoder.decode_both().unwrap();
 
-    let reader = File::open("tests/data/hotspot.xbm")
-        .map(BufReader::new)
-        .unwrap();
-    let decoder = Decoder::new(reader).unwrap();
-    assert_eq!(decoder.name(), "image");
-    assert_eq!(decoder.width(), 8);
-    assert_eq!(decoder.height(), 7);
-    assert_eq!(decoder.x_hot(), Some(4));
-    assert_eq!(decoder.y_hot(), Some(3));
-    let mut buf = [u8::default(); 56];
-    decoder.decode(&mut buf).unwrap();
-    assert_eq!(buf, *expected);
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(packed, *decoder.decode_packed().unwrap());
+
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(pixels, decoder.decode_to_vec().unwrap());
+    }
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
+        };
+    "};
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    let err = decoder.decode_both().unwrap_err();
+    if let Error::InvalidImageSize {
+        expected_pixels,
+        decoded_pixels,
+    } = err
+    {
+        assert_eq!(expected_pixels, 56);
+        assert_eq!(decoded_pixels, 48);
+    } else {
+        unreachable!();
+    }
 }
 
+#[cfg(feature = "bitvec")]
 #[test]
-fn decode_without_unsigned() {
-    // "B" (8x7)
-    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
-                     \x00\x00\x01\x01\x01\x00\x00\x00\
-                     \x00\x00\x01\x00\x00\x01\x00\x00\
-                     \x00\x00\x01\x01\x01\x00\x00\x00\
-                     \x00\x00\x01\x00\x00\x01\x00\x00\
-                     \x00\x00\x01\x01\x01\x00\x00\x00\
-                     \x00\x00\x00\x00\x00\x00\x00\x00";
+fn decode_to_bitvec() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let expected = decoder.decode_to_vec().unwrap();
 
-    let reader = File::open("tests/data/without_unsigned.xbm")
-        .map(BufReader::new)
-        .unwrap();
-    let decoder = Decoder::new(reader).unwrap();
-    assert_eq!(decoder.name(), "image");
-    assert_eq!(decoder.width(), 8);
-    assert_eq!(decoder.height(), 7);
-    assert_eq!(decoder.x_hot(), None);
-    assert_eq!(decoder.y_hot(), None);
-    let mut buf = [u8::default(); 56];
-    decoder.decode(&mut buf).unwrap();
-    assert_eq!(buf, *expected);
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let bits = decoder.decode_to_bitvec().unwrap();
+        assert!(bits.iter().by_vals().map(u8::from).eq(expected));
+
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap().invert(true);
+        let inverted = decoder.decode_to_bitvec().unwrap();
+        assert_eq!(inverted, !bits);
+    }
+
+    for (image, size) in [
+        (
+            indoc! {"
+                #define image_width 8
+                #define image_height 7
+                static unsigned char image_bits[] = {
+                    0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
+                };
+            "},
+            48,
+        ),
+        (
+            indoc! {"
+                #define image_width 8
+                #define image_height 7
+                static unsigned char image_bits[] = {
+                    0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0x00,
+                };
+            "},
+            64,
+        ),
+    ] {
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        let err = decoder.decode_to_bitvec().unwrap_err();
+        if let Error::InvalidImageSize {
+            expected_pixels,
+            decoded_pixels,
+        } = err
+        {
+            assert_eq!(expected_pixels, 56);
+            assert_eq!(decoded_pixels, size);
+        } else {
+            unreachable!();
+        }
+    }
 }
 
 #[test]
-fn decode_with_valid_identifiers() {
-    {
-        let image = indoc! {"
-            #define A_width 8
-            #define A_height 7
-            #define A_x_hot 4
-            #define A_y_hot 3
-            static unsigned char A_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
-        "};
-        let buf = Cursor::new(image);
-        let result = Decoder::new(buf);
-        assert!(result.is_ok());
-    }
-    {
-        let image = indoc! {"
-            #define a_width 8
-            #define a_height 7
-            #define a_x_hot 4
-            #define a_y_hot 3
-            static unsigned char a_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
-        "};
-        let buf = Cursor::new(image);
-        let result = Decoder::new(buf);
-        assert!(result.is_ok());
+fn set_pixels() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let width = usize::try_from(decoder.width()).unwrap();
+        let expected = decoder
+            .decode_to_vec()
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, pixel)| pixel == 1)
+            .map(|(i, _)| {
+                (
+                    u32::try_from(i % width).unwrap(),
+                    u32::try_from(i / width).unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let pixels = decoder.set_pixels().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(pixels, expected);
     }
-    {
-        let image = indoc! {"
-            #define TEST_width 8
-            #define TEST_height 7
-            #define TEST_x_hot 4
-            #define TEST_y_hot 3
-            static unsigned char TEST_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
-        "};
-        let buf = Cursor::new(image);
-        let result = Decoder::new(buf);
-        assert!(result.is_ok());
+
+    let image = indoc! {"
+        #define image_width 3
+        #define image_height 2
+        static unsigned char image_bits[] = {
+            0xFD, 0xFA,
+        };
+    "};
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    let pixels = decoder.set_pixels().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(pixels, [(0, 0), (2, 0), (1, 1)]);
+
+    let decoder = Decoder::new(Cursor::new(image)).unwrap().invert(true);
+    let pixels = decoder.set_pixels().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(pixels, [(1, 0), (0, 1), (2, 1)]);
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
+        };
+    "};
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    let mut pixels = decoder.set_pixels();
+    assert!(pixels.next().unwrap().unwrap_err().is_invalid_image_size());
+    assert!(pixels.next().is_none());
+}
+
+#[test]
+fn validate() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/basic_single_line.xbm",
+        "tests/data/hotspot.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/x10.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert!(decoder.validate().is_ok(), "{path}");
     }
-    {
-        let image = indoc! {"
-            #define test_width 8
-            #define test_height 7
-            #define test_x_hot 4
-            #define test_y_hot 3
-            static unsigned char test_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+}
+
+#[test]
+fn validate_invalid_image() {
+    for image in [
+        indoc! {"
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
             };
-        "};
-        let buf = Cursor::new(image);
-        let result = Decoder::new(buf);
-        assert!(result.is_ok());
-    }
-    {
-        let image = indoc! {"
-            #define C17_width 8
-            #define C17_height 7
-            #define C17_x_hot 4
-            #define C17_y_hot 3
-            static unsigned char C17_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        "},
+        indoc! {"
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0x00,
             };
-        "};
-        let buf = Cursor::new(image);
-        let result = Decoder::new(buf);
-        assert!(result.is_ok());
-    }
-    {
-        let image = indoc! {"
-            #define \u{30C6}\u{30B9}\u{30C8}_width 8
-            #define \u{30C6}\u{30B9}\u{30C8}_height 7
-            #define \u{30C6}\u{30B9}\u{30C8}_x_hot 4
-            #define \u{30C6}\u{30B9}\u{30C8}_y_hot 3
-            static unsigned char \u{30C6}\u{30B9}\u{30C8}_bits[] = {
+        "},
+        indoc! {"
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        "},
+        indoc! {"
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x0g,
             };
-        "};
-        let buf = Cursor::new(image);
-        let result = Decoder::new(buf);
-        assert!(result.is_ok());
+        "},
+        include_str!("data/frames.xbm"),
+    ] {
+        let expected = Decoder::new(Cursor::new(image))
+            .unwrap()
+            .decode_to_vec()
+            .unwrap_err();
+        let err = Decoder::new(Cursor::new(image))
+            .unwrap()
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.to_string(), expected.to_string());
     }
 }
 
 #[test]
-fn decode_with_invalid_identifiers() {
+fn skip_image() {
+    let reader = File::open("tests/data/frames.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.name(), "frame0");
+    let reader = decoder.skip_image().unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.name(), "frame1");
+    let buf = decoder.decode_packed().unwrap();
+    assert_eq!(*buf, *b"\xFF\xE3\xDB\xE3\xDB\xE3\xFF");
+
+    let reader = File::open("tests/data/basic_single_line.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let mut reader = Decoder::new(reader).unwrap().skip_image().unwrap();
+    assert!(reader.fill_buf().unwrap().is_empty());
+}
+
+#[test]
+fn skip_invalid_image() {
     {
         let image = indoc! {"
-            #define _width 8
-            #define _height 7
-            #define _x_hot 4
-            #define _y_hot 3
-            static unsigned char _bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let err = Decoder::new(Cursor::new(image))
+            .unwrap()
+            .skip_image()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::InvalidImageSize {
+                expected_pixels: 56,
+                decoded_pixels: 48
+            }
+        ));
     }
     {
         let image = indoc! {"
-            #define 0_width 8
-            #define 0_height 7
-            #define 0_x_hot 4
-            #define 0_y_hot 3
-            static unsigned char 0_bits[] = {
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let err = Decoder::new(Cursor::new(image))
+            .unwrap()
+            .skip_image()
+            .unwrap_err();
+        assert!(err.is_unexpected_eof());
     }
     {
         let image = indoc! {"
-            #define __width 8
-            #define __height 7
-            #define __x_hot 4
-            #define __y_hot 3
-            static unsigned char __bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x0g,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let err = Decoder::new(Cursor::new(image))
+            .unwrap()
+            .skip_image()
+            .unwrap_err();
+        assert!(err.is_parse_int());
     }
-    {
-        let image = indoc! {"
-            #define ANSI C_width 8
-            #define ANSI C_height 7
-            #define ANSI C_x_hot 4
-            #define ANSI C_y_hot 3
-            static unsigned char ANSI C_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
-        "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+}
+
+#[test]
+fn rows() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let width = usize::try_from(decoder.width()).unwrap();
+        let height = usize::try_from(decoder.height()).unwrap();
+        let expected = decoder.decode_to_vec().unwrap();
+
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let mut rows = decoder.rows();
+        for (i, expected) in expected.chunks(width).enumerate() {
+            assert_eq!(rows.len(), height - i);
+            assert_eq!(rows.next().unwrap().unwrap(), expected);
+        }
+        assert_eq!(rows.len(), 0);
+        assert!(rows.next().is_none());
     }
-    {
-        let image = indoc! {"
-            #define XBM\0_width 8
-            #define XBM\0_height 7
-            #define XBM\0_x_hot 4
+
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap().invert(true);
+    let rows = decoder.rows().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(rows.len(), 7);
+    assert_eq!(rows[1], [1, 1, 0, 0, 0, 1, 1, 1]);
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
+        };
+    "};
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    let mut rows = decoder.rows();
+    assert_eq!(rows.len(), 1);
+    assert!(rows.next().unwrap().unwrap_err().is_invalid_image_size());
+    assert_eq!(rows.len(), 0);
+    assert!(rows.next().is_none());
+}
+
+#[test]
+fn decode_to_rows() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let width = usize::try_from(decoder.width()).unwrap();
+        let height = usize::try_from(decoder.height()).unwrap();
+        let expected = decoder.decode_to_vec().unwrap();
+
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let rows = decoder.decode_to_rows().unwrap();
+        assert_eq!(rows.len(), height);
+        assert!(rows.iter().all(|row| row.len() == width));
+        assert_eq!(rows.concat(), expected);
+    }
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
+        };
+    "};
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    assert!(decoder
+        .decode_to_rows()
+        .unwrap_err()
+        .is_invalid_image_size());
+}
+
+#[test]
+fn decode_16x14() {
+    // "B" (16x14)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let reader = File::open("tests/data/16x14.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(decoder.name(), "image");
+        assert_eq!(decoder.width(), 16);
+        assert_eq!(decoder.height(), 14);
+        assert_eq!(decoder.x_hot(), None);
+        assert_eq!(decoder.y_hot(), None);
+        let mut buf = [u8::default(); 224];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+    {
+        let reader = File::open("tests/data/16x14_minified.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(decoder.name(), "image");
+        assert_eq!(decoder.width(), 16);
+        assert_eq!(decoder.height(), 14);
+        assert_eq!(decoder.x_hot(), None);
+        assert_eq!(decoder.y_hot(), None);
+        let mut buf = [u8::default(); 224];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+}
+
+#[test]
+fn decode_width_7() {
+    // "I" (7x6)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\
+                     \x00\x00\x00\x01\x00\x00\x00\
+                     \x00\x00\x00\x01\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let reader = File::open("tests/data/width_7.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(decoder.name(), "image");
+        assert_eq!(decoder.width(), 7);
+        assert_eq!(decoder.height(), 6);
+        assert_eq!(decoder.x_hot(), None);
+        assert_eq!(decoder.y_hot(), None);
+        let mut buf = [u8::default(); 42];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+    {
+        let reader = File::open("tests/data/width_7_minified.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(decoder.name(), "image");
+        assert_eq!(decoder.width(), 7);
+        assert_eq!(decoder.height(), 6);
+        assert_eq!(decoder.x_hot(), None);
+        assert_eq!(decoder.y_hot(), None);
+        let mut buf = [u8::default(); 42];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+}
+
+#[test]
+fn decode_width_14() {
+    // "I" (14x12)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let reader = File::open("tests/data/width_14.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(decoder.name(), "image");
+        assert_eq!(decoder.width(), 14);
+        assert_eq!(decoder.height(), 12);
+        assert_eq!(decoder.x_hot(), None);
+        assert_eq!(decoder.y_hot(), None);
+        let mut buf = [u8::default(); 168];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+    {
+        let reader = File::open("tests/data/width_14_minified.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(decoder.name(), "image");
+        assert_eq!(decoder.width(), 14);
+        assert_eq!(decoder.height(), 12);
+        assert_eq!(decoder.x_hot(), None);
+        assert_eq!(decoder.y_hot(), None);
+        let mut buf = [u8::default(); 168];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+}
+
+#[test]
+fn decode_with_hotspot() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let reader = File::open("tests/data/hotspot.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.name(), "image");
+    assert_eq!(decoder.width(), 8);
+    assert_eq!(decoder.height(), 7);
+    assert_eq!(decoder.x_hot(), Some(4));
+    assert_eq!(decoder.y_hot(), Some(3));
+    assert_eq!(decoder.hotspot(), Some((4, 3)));
+    let mut buf = [u8::default(); 56];
+    decoder.decode(&mut buf).unwrap();
+    assert_eq!(buf, *expected);
+}
+
+#[test]
+fn decode_with_zero_hotspot() {
+    let reader = File::open("tests/data/zero_hotspot.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.x_hot(), Some(0));
+    assert_eq!(decoder.y_hot(), Some(0));
+    assert_eq!(decoder.hotspot(), Some((0, 0)));
+    assert_eq!(decoder.metadata().hotspot(), Some((0, 0)));
+}
+
+#[test]
+fn metadata() {
+    let reader = File::open("tests/data/hotspot.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    let header = decoder.metadata();
+    assert_eq!(header.name(), decoder.name());
+    assert_eq!(header.width(), decoder.width());
+    assert_eq!(header.height(), decoder.height());
+    assert_eq!(header.x_hot(), decoder.x_hot());
+    assert_eq!(header.y_hot(), decoder.y_hot());
+    assert_eq!(header.hotspot(), decoder.hotspot());
+    assert_eq!(header.crc32(), decoder.crc32());
+
+    let handle = thread::spawn(move || header);
+    let header = handle.join().unwrap();
+    let mut buf = [u8::default(); 56];
+    decoder.decode(&mut buf).unwrap();
+    assert_eq!(header.name(), "image");
+    assert_eq!(header.width(), 8);
+    assert_eq!(header.height(), 7);
+    assert_eq!(header.x_hot(), Some(4));
+    assert_eq!(header.y_hot(), Some(3));
+    assert_eq!(header.hotspot(), Some((4, 3)));
+    assert!(header.crc32().is_none());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_metadata() {
+    use xbm::decode::Header;
+
+    let reader = File::open("tests/data/hotspot.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    let header = decoder.metadata();
+    let json = serde_json::to_string(&header).unwrap();
+    assert_eq!(
+        json,
+        r#"{"name":"image","width":8,"height":7,"x_hot":4,"y_hot":3,"crc32":null}"#
+    );
+    assert_eq!(serde_json::from_str::<Header>(&json).unwrap(), header);
+}
+
+#[test]
+fn decode_with_zero_dimension() {
+    for (width, height) in [(0, 0), (0, 7), (8, 0)] {
+        let image = formatdoc! {"
+            #define image_width {width}
+            #define image_height {height}
+            static unsigned char image_bits[] = {{
+            }};
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        assert!(matches!(err, Error::ZeroDimension));
+    }
+}
+
+#[test]
+fn decode_with_spaced_define() {
+    let reader = File::open("tests/data/spaced_define.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.name(), "image");
+    assert_eq!(decoder.width(), 8);
+    assert_eq!(decoder.height(), 7);
+    assert_eq!(decoder.x_hot(), Some(4));
+    assert_eq!(decoder.y_hot(), Some(3));
+    assert_eq!(decoder.hotspot(), Some((4, 3)));
+    let buf = decoder.decode_packed().unwrap();
+    assert_eq!(*buf, *b"\x00\x1C\x24\x1C\x24\x1C\x00");
+
+    let image = indoc! {"
+        #defineimage_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        };
+    "};
+    let err = Decoder::new(Cursor::new(image)).unwrap_err();
+    assert!(err.is_invalid_header());
+}
+
+#[test]
+fn decode_with_bom() {
+    let reader = File::open("tests/data/bom.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.name(), "image");
+    assert_eq!(decoder.width(), 8);
+    assert_eq!(decoder.height(), 7);
+    let buf = decoder.decode_packed().unwrap();
+    assert_eq!(*buf, *b"\x00\x1C\x24\x1C\x24\x1C\x00");
+
+    let reader = File::open("tests/data/bom.xbm")
+        .map(|f| BufReader::with_capacity(1, f))
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.name(), "image");
+
+    let image = "#define image_width 8\n\u{FEFF}#define image_height 7\n";
+    let err = Decoder::new(Cursor::new(image)).unwrap_err();
+    assert!(err.is_invalid_header());
+}
+
+#[test]
+fn decode_with_leading_comment() {
+    {
+        let reader = File::open("tests/data/leading_comment.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(
+            decoder.leading_comment(),
+            Some("/*\n * The letter \"B\".\n */\n\n// Converted from basic.xbm.")
+        );
+        assert_eq!(decoder.name(), "image");
+        let buf = decoder.decode_packed().unwrap();
+        assert_eq!(*buf, *b"\x00\x1C\x24\x1C\x24\x1C\x00");
+    }
+    {
+        let reader = File::open("tests/data/basic.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert!(decoder.leading_comment().is_none());
+    }
+    {
+        let image = indoc! {"
+
+            /* comment */
+
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        assert_eq!(decoder.leading_comment(), Some("/* comment */"));
+        assert!(decoder.decode_to_vec().is_ok());
+    }
+    {
+        let image = indoc! {"
+            /* comment */ #define image_width 8
+            #define image_height 7
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        if let Error::InvalidHeader { line_no, text } = err {
+            assert_eq!(line_no, 1);
+            assert_eq!(text, "/* comment */ #define image_width 8");
+        } else {
+            unreachable!();
+        }
+    }
+    {
+        let image = indoc! {"
+            /* comment
+            #define image_width 8
+            #define image_height 7
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        if let Error::InvalidHeader { line_no, .. } = err {
+            assert_eq!(line_no, 4);
+        } else {
+            unreachable!();
+        }
+    }
+}
+
+#[test]
+fn decode_with_trailing_comments_on_define() {
+    for path in [
+        "tests/data/define_block_comments.xbm",
+        "tests/data/define_line_comments.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(decoder.name(), "image");
+        assert_eq!(decoder.width(), 8);
+        assert_eq!(decoder.height(), 7);
+        assert_eq!(decoder.hotspot(), Some((4, 3)));
+        let buf = decoder.decode_packed().unwrap();
+        assert_eq!(*buf, *b"\x00\x1C\x24\x1C\x24\x1C\x00");
+    }
+
+    {
+        let image = indoc! {"
+            #define image_width 8 /* pixels */ 1
+            #define image_height 7
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        if let Error::InvalidHeader { line_no, text } = err {
+            assert_eq!(line_no, 1);
+            assert_eq!(text, "#define image_width 8 /* pixels */ 1");
+        } else {
+            unreachable!();
+        }
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_crc32 0x8A8C6AE2 // checksum
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        assert_eq!(decoder.crc32(), Some(0x8A8C_6AE2));
+    }
+}
+
+#[test]
+fn decode_with_array_comment() {
+    let reader = File::open("tests/data/array_comment.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.name(), "image");
+    let buf = decoder.decode_packed().unwrap();
+    assert_eq!(*buf, *b"\x00\x1C\x24\x1C\x24\x1C\x00");
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        #define image_x_hot 4
+        #define image_y_hot 3
+
+        // 8x7 icon
+        /* with hotspot */
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        };
+    "};
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    assert_eq!(decoder.hotspot(), Some((4, 3)));
+    let buf = decoder.decode_packed().unwrap();
+    assert_eq!(*buf, *b"\x00\x1C\x24\x1C\x24\x1C\x00");
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        /* 8x7 icon */
+    "};
+    let err = Decoder::new(Cursor::new(image)).unwrap_err();
+    if let Error::InvalidHeader { line_no, text } = err {
+        assert_eq!(line_no, 4);
+        assert!(text.is_empty());
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn decode_with_hotspot_before_dimensions() {
+    for header in [
+        "#define image_x_hot 4\n#define image_y_hot 3\n#define image_width 8\n#define image_height 7",
+        "#define image_y_hot 3\n#define image_x_hot 4\n#define image_width 8\n#define image_height 7",
+        "#define image_width 8\n#define image_x_hot 4\n#define image_y_hot 3\n#define image_height 7",
+        "#define image_width 8\n#define image_y_hot 3\n#define image_height 7\n#define image_x_hot 4",
+    ] {
+        let image = formatdoc! {"
+            {header}
+            static unsigned char image_bits[] = {{
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            }};
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        assert!(matches!(err, Error::HotspotBeforeDimensions));
+    }
+}
+
+#[test]
+fn decode_with_duplicate_define() {
+    for (defines, field) in [
+        ("#define image_width 8\n#define image_width 8", "width"),
+        ("#define image_width 8\n#define image_height 7\n#define image_width 16", "width"),
+        ("#define image_width 8\n#define image_height 7\n#define image_height 7", "height"),
+        (
+            "#define image_width 8\n#define image_height 7\n#define image_x_hot 4\n#define image_x_hot 4",
+            "x_hot",
+        ),
+        (
+            "#define image_width 8\n#define image_height 7\n#define image_x_hot 4\n#define image_y_hot 3\n#define image_y_hot 3",
+            "y_hot",
+        ),
+        (
+            "#define image_width 8\n#define image_height 7\n#define image_crc32 0x00000000\n#define image_crc32 0x00000000",
+            "crc32",
+        ),
+    ] {
+        let image = formatdoc! {"
+            {defines}
+            static unsigned char image_bits[] = {{
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            }};
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        if let Error::DuplicateDefine(f) = err {
+            assert_eq!(f, field);
+        } else {
+            unreachable!();
+        }
+    }
+}
+
+#[test]
+fn decode_with_mixed_radix_header() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 0x7
+        #define image_x_hot 0x4
+        #define image_y_hot 3
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        };
+    "};
+    let image = Cursor::new(image);
+    let decoder = Decoder::new(image).unwrap();
+    assert_eq!(decoder.width(), 8);
+    assert_eq!(decoder.height(), 7);
+    assert_eq!(decoder.x_hot(), Some(4));
+    assert_eq!(decoder.y_hot(), Some(3));
+    assert_eq!(decoder.hotspot(), Some((4, 3)));
+    let mut buf = [u8::default(); 56];
+    decoder.decode(&mut buf).unwrap();
+    assert_eq!(buf, *expected);
+}
+
+#[test]
+fn decode_with_hex_dimensions() {
+    let reader = File::open("tests/data/hex_dimensions.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.width(), 16);
+    assert_eq!(decoder.height(), 14);
+    let buf = decoder.decode_to_vec().unwrap();
+
+    let reader = File::open("tests/data/16x14.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let expected = Decoder::new(reader).unwrap().decode_to_vec().unwrap();
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn decode_with_arithmetic_header() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let image = indoc! {"
+        #define image_width (4 * 2)
+        #define image_height (3+4)
+        #define image_x_hot (0x2 * 2)
+        #define image_y_hot (3)
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        };
+    "};
+    let image = Cursor::new(image);
+    let decoder = Decoder::new(image).unwrap();
+    assert_eq!(decoder.width(), 8);
+    assert_eq!(decoder.height(), 7);
+    assert_eq!(decoder.x_hot(), Some(4));
+    assert_eq!(decoder.y_hot(), Some(3));
+    assert_eq!(decoder.hotspot(), Some((4, 3)));
+    let mut buf = [u8::default(); 56];
+    decoder.decode(&mut buf).unwrap();
+    assert_eq!(buf, *expected);
+}
+
+#[test]
+fn decode_with_invalid_arithmetic_header() {
+    for value in [
+        "(4 * 2",
+        "4 * 2)",
+        "4 * 2",
+        "(4 - 2)",
+        "(65536 * 65536)",
+        "(4294967295 + 1)",
+    ] {
+        let image = formatdoc! {"
+            #define image_width {value}
+            #define image_height 7
+            static unsigned char image_bits[] = {{
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            }};
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::InvalidHeader { .. } | Error::ParseInt(_)
+        ));
+    }
+    {
+        let image = indoc! {"
+            #define image_width (4 * 2 * 1)
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::InvalidDigit
+        );
+    }
+}
+
+#[test]
+fn decode_with_crc32() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let reader = File::open("tests/data/crc32.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(decoder.name(), "image");
+        assert_eq!(decoder.width(), 8);
+        assert_eq!(decoder.height(), 7);
+        assert_eq!(decoder.x_hot(), None);
+        assert_eq!(decoder.y_hot(), None);
+        assert_eq!(decoder.crc32(), Some(0x1EC5_CF1C));
+        let mut buf = [u8::default(); 56];
+        decoder.verify_crc32(true).decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_x_hot 4
+            #define image_y_hot 3
+            #define image_crc32 0x1EC5CF1C
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        assert_eq!(decoder.x_hot(), Some(4));
+        assert_eq!(decoder.y_hot(), Some(3));
+        assert_eq!(decoder.crc32(), Some(0x1EC5_CF1C));
+        let mut buf = [u8::default(); 56];
+        decoder.verify_crc32(true).decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+}
+
+#[test]
+fn decode_with_invalid_crc32() {
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        #define image_crc32 0x1EC5CF1C
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x01,
+        };
+    "};
+    {
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        let mut buf = [u8::default(); 56];
+        decoder.decode(&mut buf).unwrap();
+    }
+    {
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        let mut buf = [u8::default(); 56];
+        let err = decoder.verify_crc32(true).decode(&mut buf).unwrap_err();
+        if let Error::ChecksumMismatch { expected, actual } = err {
+            assert_eq!(expected, 0x1EC5_CF1C);
+            assert_ne!(actual, expected);
+        } else {
+            unreachable!();
+        }
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_crc32 1EC5CF1C
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_crc32 0x1EC5CF1C
+            #define image_x_hot 4
+            #define image_y_hot 3
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+}
+
+#[test]
+fn decode_with_comments() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    for image in [
+        indoc! {"
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, /* [0] */ 0x1C, /* [1] */ 0x24, /* [2] */ 0x1C, /* [3] */
+                0x24, /* [4] */ 0x1C, /* [5] */ 0x00, /* [6] */
+            };
+        "},
+        indoc! {"
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, /* ........ */
+                0x1C, /* ..###... */
+                0x24, /* ..#..#.. */
+                0x1C, // ..###...
+                0x24, // ..#..#..
+                0x1C, /* ..###... */
+                0x00, /* ........ */
+            };
+        "},
+    ] {
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        let mut buf = [u8::default(); 56];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+
+        #[cfg(feature = "rayon")]
+        {
+            let decoder = Decoder::new(Cursor::new(image)).unwrap();
+            let mut buf = [u8::default(); 56];
+            decoder.decode_parallel(&mut buf).unwrap();
+            assert_eq!(buf, *expected);
+        }
+    }
+}
+
+#[test]
+fn decode_with_brace_on_next_line() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let reader = File::open("tests/data/brace_on_next_line.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    let mut buf = [u8::default(); 56];
+    decoder.decode(&mut buf).unwrap();
+    assert_eq!(buf, *expected);
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] =
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        };
+    "};
+    let err = Decoder::new(Cursor::new(image)).unwrap_err();
+    if let Error::InvalidHeader { line_no, .. } = err {
+        assert_eq!(line_no, 4);
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn decode_with_comment_and_brace_on_next_line() {
+    let expected = Decoder::from_bytes(include_bytes!("data/basic.xbm"))
+        .unwrap()
+        .decode_to_vec()
+        .unwrap();
+
+    let reader = File::open("tests/data/brace_on_next_line_with_comment.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.decode_to_vec().unwrap(), expected);
+
+    for declaration in [
+        "static unsigned char image_bits[] = // data\n{",
+        "static unsigned char image_bits[] = /* { */\n{",
+        "static unsigned char image_bits[] = /* data */\n\n/* rows */\n{",
+        "static unsigned char image_bits[] /* data */ =\n{ // rows",
+        "static unsigned char image_bits[] =\n/* data */ {",
+    ] {
+        let image = formatdoc! {"
+            #define image_width 8
+            #define image_height 7
+            {declaration}
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            }};
+        "};
+        let decoder = Decoder::new(Cursor::new(&image)).unwrap();
+        assert_eq!(decoder.decode_to_vec().unwrap(), expected, "{declaration}");
+        let decoder = Decoder::new(Cursor::new(&image))
+            .unwrap()
+            .stream_array(true);
+        assert_eq!(decoder.decode_to_vec().unwrap(), expected, "{declaration}");
+    }
+
+    for (image, expected) in [
+        (
+            indoc! {"
+                #define image_width 8
+                #define image_height 7
+                static unsigned char image_bits[] = /* data */
+                    0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+                };
+            "},
+            4,
+        ),
+        (
+            indoc! {"
+                #define image_width 8
+                #define image_height 7
+                static unsigned char image_bits[] = /* data */ 0x00
+                {
+                    0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+                };
+            "},
+            3,
+        ),
+        (
+            indoc! {"
+                #define image_width 8
+                #define image_height 7
+                static unsigned char image_bits[] = /* data */
+            "},
+            4,
+        ),
+    ] {
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        if let Error::InvalidHeader { line_no, .. } = err {
+            assert_eq!(line_no, expected);
+        } else {
+            unreachable!();
+        }
+    }
+}
+
+#[test]
+fn decode_without_unsigned() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let reader = File::open("tests/data/without_unsigned.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.name(), "image");
+    assert_eq!(decoder.width(), 8);
+    assert_eq!(decoder.height(), 7);
+    assert_eq!(decoder.x_hot(), None);
+    assert_eq!(decoder.y_hot(), None);
+    let mut buf = [u8::default(); 56];
+    decoder.decode(&mut buf).unwrap();
+    assert_eq!(buf, *expected);
+}
+
+#[test]
+fn decode_with_compact_array_declaration() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    for declaration in [
+        "static unsigned char image_bits[]={",
+        "static unsigned char image_bits[] ={",
+        "static unsigned char image_bits[]= {",
+        "static char image_bits[]={",
+    ] {
+        let image = formatdoc! {"
+            #define image_width 8
+            #define image_height 7
+            {declaration}
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            }};
+        "};
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        assert_eq!(decoder.name(), "image");
+        let mut buf = [u8::default(); 56];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+}
+
+#[test]
+fn decode_with_spaced_array_declaration() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    for declaration in [
+        "static  unsigned  char  image_bits[]  =  {",
+        "static\tunsigned\tchar\timage_bits[]\t=\t{",
+        "  static unsigned char image_bits[] = {",
+        "static \t char image_bits[] \t= \t{",
+    ] {
+        let image = formatdoc! {"
+            #define image_width 8
+            #define image_height 7
+            {declaration}
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            }};
+        "};
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        assert_eq!(decoder.name(), "image");
+        let mut buf = [u8::default(); 56];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+}
+
+#[test]
+fn decode_with_surrounding_blank_lines() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let image = indoc! {"
+
+            \x20\x20
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+
+
+        "};
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        assert_eq!(decoder.width(), 8);
+        assert_eq!(decoder.height(), 7);
+        let mut buf = [u8::default(); 56];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+    {
+        let image = indoc! {"
+            \t
+              #define image_width 8
+              #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        assert_eq!(decoder.width(), 8);
+        assert_eq!(decoder.height(), 7);
+        let mut buf = [u8::default(); 56];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+}
+
+#[test]
+fn decode_from_blank_input() {
+    let image = Cursor::new("\n  \n\t\n");
+    let err = Decoder::new(image).unwrap_err();
+    assert!(matches!(err, Error::InvalidHeader { .. }));
+}
+
+#[test]
+fn decode_with_valid_identifiers() {
+    {
+        let image = indoc! {"
+            #define A_width 8
+            #define A_height 7
+            #define A_x_hot 4
+            #define A_y_hot 3
+            static unsigned char A_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let result = Decoder::new(buf);
+        assert!(result.is_ok());
+    }
+    {
+        let image = indoc! {"
+            #define a_width 8
+            #define a_height 7
+            #define a_x_hot 4
+            #define a_y_hot 3
+            static unsigned char a_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let result = Decoder::new(buf);
+        assert!(result.is_ok());
+    }
+    {
+        let image = indoc! {"
+            #define TEST_width 8
+            #define TEST_height 7
+            #define TEST_x_hot 4
+            #define TEST_y_hot 3
+            static unsigned char TEST_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let result = Decoder::new(buf);
+        assert!(result.is_ok());
+    }
+    {
+        let image = indoc! {"
+            #define test_width 8
+            #define test_height 7
+            #define test_x_hot 4
+            #define test_y_hot 3
+            static unsigned char test_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let result = Decoder::new(buf);
+        assert!(result.is_ok());
+    }
+    {
+        let image = indoc! {"
+            #define C17_width 8
+            #define C17_height 7
+            #define C17_x_hot 4
+            #define C17_y_hot 3
+            static unsigned char C17_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let result = Decoder::new(buf);
+        assert!(result.is_ok());
+    }
+    {
+        let image = indoc! {"
+            #define \u{30C6}\u{30B9}\u{30C8}_width 8
+            #define \u{30C6}\u{30B9}\u{30C8}_height 7
+            #define \u{30C6}\u{30B9}\u{30C8}_x_hot 4
+            #define \u{30C6}\u{30B9}\u{30C8}_y_hot 3
+            static unsigned char \u{30C6}\u{30B9}\u{30C8}_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let result = Decoder::new(buf);
+        assert!(result.is_ok());
+    }
+}
+
+#[test]
+fn decode_with_invalid_identifiers() {
+    {
+        let image = indoc! {"
+            #define _width 8
+            #define _height 7
+            #define _x_hot 4
+            #define _y_hot 3
+            static unsigned char _bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define 0_width 8
+            #define 0_height 7
+            #define 0_x_hot 4
+            #define 0_y_hot 3
+            static unsigned char 0_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define __width 8
+            #define __height 7
+            #define __x_hot 4
+            #define __y_hot 3
+            static unsigned char __bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define ANSI C_width 8
+            #define ANSI C_height 7
+            #define ANSI C_x_hot 4
+            #define ANSI C_y_hot 3
+            static unsigned char ANSI C_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define XBM\0_width 8
+            #define XBM\0_height 7
+            #define XBM\0_x_hot 4
             #define XBM\0_y_hot 3
             static unsigned char XBM\0_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define \u{1F980}_width 8
+            #define \u{1F980}_height 7
+            #define \u{1F980}_x_hot 4
+            #define \u{1F980}_y_hot 3
+            static unsigned char \u{1F980}_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+}
+
+#[test]
+fn decode_with_invalid_width_statement() {
+    {
+        let image = indoc! {"
+            #include image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define test_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8 16
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+}
+
+#[test]
+fn decode_with_invalid_height_statement() {
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #include image_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define test_height 7
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7 14
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+}
+
+#[test]
+fn decode_with_invalid_x_hot_statement() {
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #include image_x_hot 4
+            #define image_y_hot 3
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define test_x_hot 4
+            #define image_y_hot 3
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_y_hot 3
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::IncompleteHotspot));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_x_hot
+            #define image_y_hot 3
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_x_hot 4 8
+            #define image_y_hot 3
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+}
+
+#[test]
+fn decode_with_invalid_y_hot_statement() {
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_x_hot 4
+            #include image_y_hot 3
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::IncompleteHotspot));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_x_hot 4
+            #define test_y_hot 3
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_x_hot 4
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::IncompleteHotspot));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_x_hot 4
+            #define image_y_hot
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_x_hot 4
+            #define image_y_hot 3 6
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { .. }));
+    }
+}
+
+#[test]
+fn decode_with_extra_defines() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let reader = File::open("tests/data/extra_defines.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(decoder.dimensions(), (8, 7));
+        assert_eq!(decoder.hotspot(), Some((4, 3)));
+        assert_eq!(
+            decoder.extra_defines(),
+            [
+                (String::from("image_depth"), String::from("1")),
+                (String::from("image_format"), String::new())
+            ]
+        );
+        assert_eq!(decoder.decode_to_vec().unwrap(), expected);
+    }
+    {
+        let reader = File::open("tests/data/basic.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert!(decoder.extra_defines().is_empty());
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define icon_depth 1
+            #define image_height 7
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { line_no: 2, .. }));
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_depth 1
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        assert!(matches!(err, Error::InvalidHeader { line_no: 3, .. }));
+    }
+}
+
+#[test]
+fn decode_with_invalid_header_line() {
+    {
+        let image = indoc! {"
+
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        if let Error::InvalidHeader { line_no, text } = &err {
+            assert_eq!(*line_no, 4);
+            assert_eq!(text, "static unsigned char image_bits = {");
+        } else {
+            unreachable!();
+        }
+        assert_eq!(
+            err.to_string(),
+            r#"invalid header at line 4: "static unsigned char image_bits = {""#
+        );
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height (7 + 1
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        if let Error::InvalidHeader { line_no, text } = err {
+            assert_eq!(line_no, 2);
+            assert_eq!(text, "#define image_height (7 + 1");
+        } else {
+            unreachable!();
+        }
     }
     {
         let image = indoc! {"
-            #define \u{1F980}_width 8
-            #define \u{1F980}_height 7
-            #define \u{1F980}_x_hot 4
-            #define \u{1F980}_y_hot 3
-            static unsigned char \u{1F980}_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
+            #define image_width 8
+            #define image_height 7
+            #define image_x_hot 4
+            #define image_y_hot 3
+            #define icon_depth 1
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let err = Decoder::new(Cursor::new(image)).unwrap_err();
+        if let Error::InvalidHeader { line_no, text } = err {
+            assert_eq!(line_no, 5);
+            assert_eq!(text, "#define icon_depth 1");
+        } else {
+            unreachable!();
+        }
     }
 }
 
 #[test]
-fn decode_with_invalid_width_statement() {
+#[allow(clippy::too_many_lines)]
+fn decode_with_invalid_array_declaration() {
     {
         let image = indoc! {"
-            #include image_width 8
+            #define image_width 8
             #define image_height 7
-            static unsigned char image_bits[] = {
+            static unsigned int image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert!(matches!(err, Error::InvalidHeader { .. }));
     }
     {
         let image = indoc! {"
-            #define test_width 8
+            #define image_width 8
             #define image_height 7
-            static unsigned char image_bits[] = {
+            static long image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert!(matches!(err, Error::InvalidHeader { .. }));
     }
     {
         let image = indoc! {"
+            #define image_width 8
             #define image_height 7
-            static unsigned char image_bits[] = {
+            static unsigned char test_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert!(matches!(err, Error::InvalidHeader { .. }));
     }
     {
         let image = indoc! {"
-            #define image_width
+            #define image_width 8
             #define image_height 7
-            static unsigned char image_bits[] = {
+            static unsigned char image _bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert!(matches!(err, Error::InvalidHeader { .. }));
     }
     {
         let image = indoc! {"
-            #define image_width 8 16
+            #define image_width 8
             #define image_height 7
-            static unsigned char image_bits[] = {
+            static unsigned char image_ bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert!(matches!(err, Error::InvalidHeader { .. }));
     }
-}
-
-#[test]
-fn decode_with_invalid_height_statement() {
     {
         let image = indoc! {"
             #define image_width 8
-            #include image_height 7
-            static unsigned char image_bits[] = {
+            #define image_height 7
+            static unsigned char image_bits [] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert!(matches!(err, Error::InvalidHeader { .. }));
     }
     {
         let image = indoc! {"
             #define image_width 8
-            #define test_height 7
-            static unsigned char image_bits[] = {
+            #define image_height 7
+            static unsigned char image_bits() = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert!(matches!(err, Error::InvalidHeader { .. }));
     }
     {
         let image = indoc! {"
             #define image_width 8
-            static unsigned char image_bits[] = {
+            #define image_height 7
+            static unsigned char image_bits[] + {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert!(matches!(err, Error::InvalidHeader { .. }));
     }
     {
         let image = indoc! {"
             #define image_width 8
-            #define image_height
-            static unsigned char image_bits[] = {
+            #define image_height 7
+            static unsigned char image_bits[] == {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert!(matches!(err, Error::InvalidHeader { .. }));
     }
     {
         let image = indoc! {"
             #define image_width 8
-            #define image_height 7 14
-            static unsigned char image_bits[] = {
+            #define image_height 7
+            static unsigned char image_bits[] = [
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
+            ];
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert!(matches!(err, Error::InvalidHeader { .. }));
     }
 }
 
 #[test]
-fn decode_with_invalid_x_hot_statement() {
+fn decode_with_invalid_width_value() {
     {
         let image = indoc! {"
-            #define image_width 8
+            #define image_width 4294967296
             #define image_height 7
-            #include image_x_hot 4
-            #define image_y_hot 3
             static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::PosOverflow
+        );
     }
     {
         let image = indoc! {"
-            #define image_width 8
+            #define image_width -1
             #define image_height 7
-            #define test_x_hot 4
-            #define image_y_hot 3
             static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::InvalidDigit
+        );
     }
     {
         let image = indoc! {"
-            #define image_width 8
+            #define image_width a
             #define image_height 7
-            #define image_y_hot 3
             static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::InvalidDigit
+        );
     }
+}
+
+#[test]
+fn decode_with_invalid_height_value() {
     {
         let image = indoc! {"
             #define image_width 8
-            #define image_height 7
-            #define image_x_hot
-            #define image_y_hot 3
+            #define image_height 4294967296
             static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::PosOverflow
+        );
     }
     {
         let image = indoc! {"
             #define image_width 8
-            #define image_height 7
-            #define image_x_hot 4 8
-            #define image_y_hot 3
+            #define image_height -1
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::InvalidDigit
+        );
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height a
             static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::InvalidDigit
+        );
     }
 }
 
 #[test]
-fn decode_with_invalid_y_hot_statement() {
+fn decode_with_invalid_x_hot_value() {
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            #define image_x_hot 4
-            #include image_y_hot 3
+            #define image_x_hot 4294967296
+            #define image_y_hot 3
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+        "};
+        let buf = Cursor::new(image);
+        let err = Decoder::new(buf).unwrap_err();
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::PosOverflow
+        );
+    }
+    {
+        let image = indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_x_hot -1
+            #define image_y_hot 3
             static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::InvalidDigit
+        );
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            #define image_x_hot 4
-            #define test_y_hot 3
+            #define image_x_hot a
+            #define image_y_hot 3
             static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::InvalidDigit
+        );
     }
+}
+
+#[test]
+fn decode_with_invalid_y_hot_value() {
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
             #define image_x_hot 4
+            #define image_y_hot 4294967296
             static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::PosOverflow
+        );
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
             #define image_x_hot 4
-            #define image_y_hot
+            #define image_y_hot -1
             static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::InvalidDigit
+        );
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
             #define image_x_hot 4
-            #define image_y_hot 3 6
+            #define image_y_hot a
             static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
         let buf = Cursor::new(image);
         let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        assert_eq!(
+            err.source()
+                .unwrap()
+                .downcast_ref::<ParseIntError>()
+                .unwrap()
+                .kind(),
+            &IntErrorKind::InvalidDigit
+        );
     }
 }
 
 #[test]
-#[allow(clippy::too_many_lines)]
-fn decode_with_invalid_array_declaration() {
+fn decode_with_invalid_hex_byte() {
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static unsigned short image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            static unsigned char image_bits[] = {
+                \u{1F980}, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        let err = decoder.decode(&mut buf).unwrap_err();
+        if let Error::InvalidHexByte { value, offset } = err {
+            assert_eq!(value, "\u{1F980}");
+            assert_eq!(offset, 87);
+        } else {
+            unreachable!();
+        }
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static short image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 1c, 0x24, 0x1C, 0x00,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        let err = decoder.decode(&mut buf).unwrap_err();
+        if let Error::InvalidHexByte { value, offset } = err {
+            assert_eq!(value, "1c");
+            assert_eq!(offset, 105);
+        } else {
+            unreachable!();
+        }
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static unsigned char test_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0b00,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        let err = decoder.decode(&mut buf).unwrap_err();
+        if let Error::InvalidHexByte { value, offset } = err {
+            assert_eq!(value, "0b00");
+            assert_eq!(offset, 123);
+        } else {
+            unreachable!();
+        }
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static  unsigned  char  image_bits[]  =  {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            static unsigned char image_bits[] = { /* row 0 */
+                0x00, /* 1 */ 0x1C, 0x24, // 3
+                0x1C, 0x24,
+
+                0x1C, 0x0zz,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let err = decoder.decode_to_vec().unwrap_err();
+        if let Error::InvalidHexByte { value, offset } = err {
+            assert_eq!(value, "0x0zz");
+            assert_eq!(offset, 157);
+        } else {
+            unreachable!();
+        }
+    }
+}
+
+#[test]
+fn decode_with_hex_byte_split_by_newline() {
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x0
+        0, };
+    "};
+    let image = Cursor::new(image);
+    let decoder = Decoder::new(image).unwrap();
+    let mut buf = [u8::default(); 56];
+    let err = decoder.decode(&mut buf).unwrap_err();
+    if let Error::InvalidHexByte { value, offset } = err {
+        assert_eq!(value, "0x0");
+        assert_eq!(offset, 123);
+    } else {
+        unreachable!();
     }
+}
+
+#[test]
+fn decode_from_one_byte_buffer() {
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let reader = File::open("tests/data/basic.xbm")
+        .map(|f| BufReader::with_capacity(1, f))
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    let mut buf = [u8::default(); 56];
+    decoder.decode(&mut buf).unwrap();
+    assert_eq!(buf.as_slice(), expected);
+}
+
+#[test]
+fn decode_with_invalid_termination() {
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static unsigned char image_bits[]={
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
+            static unsigned char image_bits[] = {
+                0x00, 0x1C }; 0x24, 0x1C, 0x24, 0x1C, 0x00,
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        let err = decoder.decode(&mut buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidTermination));
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static unsigned char image _bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
+            static unsigned char image_bits[] = {
+                0x00, 0x1C};0x24, 0x1C, 0x24, 0x1C, 0x00,
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        let err = decoder.decode(&mut buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidTermination));
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static unsigned char image_ bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, }; 0x24, 0x1C, 0x24, 0x1C, 0x00,
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        let err = decoder.decode(&mut buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidTermination));
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static unsigned char image_bits [] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
+            static unsigned char image_bits[] = {
+                0x00, 0x1C,};0x24, 0x1C, 0x24, 0x1C, 0x00,
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        let err = decoder.decode(&mut buf).unwrap_err();
+        assert!(matches!(err, Error::InvalidTermination));
     }
+
     {
+        // "B" (8x7)
+        let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                         \x00\x00\x01\x01\x01\x00\x00\x00\
+                         \x00\x00\x01\x00\x00\x01\x00\x00\
+                         \x00\x00\x01\x01\x01\x00\x00\x00\
+                         \x00\x00\x01\x00\x00\x01\x00\x00\
+                         \x00\x00\x01\x01\x01\x00\x00\x00\
+                         \x00\x00\x00\x00\x00\x00\x00\x00";
+
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static unsigned char image_bits() = {
+            static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
+            };\x20
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
     }
+
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static unsigned char image_bits[] + {
+            static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        let err = decoder.decode(&mut buf).unwrap_err();
+        assert!(matches!(err, Error::UnexpectedEof));
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static unsigned char image_bits[] = [
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            ];
+            static unsigned char image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidHeader));
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        let err = decoder.decode(&mut buf).unwrap_err();
+        assert!(matches!(err, Error::UnexpectedEof));
     }
 }
 
 #[test]
-fn decode_with_invalid_width_value() {
+fn decode_with_truncated_image() {
     {
         let image = indoc! {"
-            #define image_width 4294967296
+            #define image_width 8
             #define image_height 7
             static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
-                .unwrap()
-                .kind(),
-            &IntErrorKind::PosOverflow
-        );
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let err = decoder.decode_to_vec().unwrap_err();
+        assert!(matches!(err, Error::UnexpectedEof));
     }
     {
         let image = indoc! {"
-            #define image_width -1
+            #define image_width 8
             #define image_height 7
             static unsigned char image_bits[] = {
                 0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
+            }
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
-                .unwrap()
-                .kind(),
-            &IntErrorKind::InvalidDigit
-        );
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        let err = decoder.decode_to_vec().unwrap_err();
+        assert!(matches!(err, Error::InvalidTermination));
+
+        #[cfg(feature = "rayon")]
+        {
+            let decoder = Decoder::new(Cursor::new(image)).unwrap();
+            let mut buf = [u8::default(); 56];
+            let err = decoder.decode_parallel(&mut buf).unwrap_err();
+            assert!(matches!(err, Error::InvalidTermination));
+        }
     }
-    {
-        let image = indoc! {"
-            #define image_width a
-            #define image_height 7
-            static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
-        "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
-                .unwrap()
-                .kind(),
-            &IntErrorKind::InvalidDigit
-        );
+}
+
+#[test]
+fn decode_with_termination_on_last_line() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    for path in [
+        "tests/data/termination_with_comma.xbm",
+        "tests/data/termination_without_comma.xbm",
+        "tests/data/termination_with_space.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let mut buf = [u8::default(); 56];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected, "{path}");
+
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let buf = decoder.decode_packed().unwrap();
+        assert_eq!(*buf, *b"\x00\x1C\x24\x1C\x24\x1C\x00", "{path}");
+
+        #[cfg(feature = "rayon")]
+        {
+            let reader = File::open(path).map(BufReader::new).unwrap();
+            let decoder = Decoder::new(reader).unwrap();
+            let mut buf = [u8::default(); 56];
+            decoder.decode_parallel(&mut buf).unwrap();
+            assert_eq!(buf, *expected, "{path}");
+        }
+
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        assert!(xbm::lint(reader).unwrap().is_empty(), "{path}");
     }
 }
 
 #[test]
-fn decode_with_invalid_height_value() {
+fn decode_with_invalid_image_size() {
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
+        };
+    "};
+    let image = Cursor::new(image);
+    let decoder = Decoder::new(image).unwrap();
+    let mut buf = [u8::default(); 56];
+    let err = decoder.decode(&mut buf).unwrap_err();
+    if let Error::InvalidImageSize {
+        expected_pixels,
+        decoded_pixels,
+    } = err
     {
-        let image = indoc! {"
-            #define image_width 8
-            #define image_height 4294967296
-            static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
-        "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
-                .unwrap()
-                .kind(),
-            &IntErrorKind::PosOverflow
-        );
+        assert_eq!(expected_pixels, 56);
+        assert_eq!(decoded_pixels, 48);
+    } else {
+        unreachable!();
     }
+}
+
+#[test]
+fn decode_with_row_length() {
+    // "I" (14x3)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+
     {
         let image = indoc! {"
-            #define image_width 8
-            #define image_height -1
+            #define image_width 14
+            #define image_height 3
             static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+                0x00, 0x00,
+                0xF0, 0x03,
+                0x00, 0x00,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
-                .unwrap()
-                .kind(),
-            &IntErrorKind::InvalidDigit
-        );
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap().verify_row_length(true);
+        let mut buf = [u8::default(); 42];
+        decoder.decode(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
     }
     {
         let image = indoc! {"
-            #define image_width 8
-            #define image_height a
+            #define image_width 14
+            #define image_height 3
             static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
+                0x00, 0x00,
+                0xF0, 0x03, 0x00,
+                0x00, };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
+        {
+            let decoder = Decoder::new(Cursor::new(image)).unwrap();
+            let mut buf = [u8::default(); 42];
+            decoder.decode(&mut buf).unwrap();
+            assert_eq!(buf, *expected);
+        }
+        {
+            let decoder = Decoder::new(Cursor::new(image))
                 .unwrap()
-                .kind(),
-            &IntErrorKind::InvalidDigit
-        );
+                .verify_row_length(true);
+            let mut buf = [u8::default(); 42];
+            let err = decoder.decode(&mut buf).unwrap_err();
+            if let Error::RowLengthMismatch {
+                row,
+                expected,
+                found,
+            } = err
+            {
+                assert_eq!(row, 1);
+                assert_eq!(expected, 2);
+                assert_eq!(found, 3);
+            } else {
+                unreachable!();
+            }
+        }
+    }
+    {
+        let reader = File::open("tests/data/16x14.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap().verify_row_length(true);
+        let mut buf = [u8::default(); 224];
+        let err = decoder.decode(&mut buf).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::RowLengthMismatch {
+                row: 0,
+                expected: 2,
+                found: 12
+            }
+        ));
     }
 }
 
 #[test]
-fn decode_with_invalid_x_hot_value() {
+fn decode_from_too_large_image() {
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0x00,
+        };
+    "};
+    let image = Cursor::new(image);
+    let decoder = Decoder::new(image).unwrap();
+    let mut buf = [u8::default(); 56];
+    let err = decoder.decode(&mut buf).unwrap_err();
+    if let Error::InvalidImageSize {
+        expected_pixels,
+        decoded_pixels,
+    } = err
     {
-        let image = indoc! {"
-            #define image_width 8
-            #define image_height 7
-            #define image_x_hot 4294967296
-            #define image_y_hot 3
-            static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };
-        "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
-                .unwrap()
-                .kind(),
-            &IntErrorKind::PosOverflow
-        );
+        assert_eq!(expected_pixels, 56);
+        assert_eq!(decoded_pixels, 64);
+    } else {
+        unreachable!();
     }
+}
+
+#[test]
+fn decode_clamped() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            #define image_x_hot -1
-            #define image_y_hot 3
             static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0xFF, 0xFF,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
-                .unwrap()
-                .kind(),
-            &IntErrorKind::InvalidDigit
-        );
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        decoder.decode_clamped(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
+    {
+        let reader = File::open("tests/data/basic.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let mut buf = [u8::default(); 56];
+        decoder.decode_clamped(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            #define image_x_hot a
-            #define image_y_hot 3
             static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
-                .unwrap()
-                .kind(),
-            &IntErrorKind::InvalidDigit
-        );
+        let image = Cursor::new(image);
+        let decoder = Decoder::new(image).unwrap();
+        let mut buf = [u8::default(); 56];
+        let err = decoder.decode_clamped(&mut buf).unwrap_err();
+        if let Error::InvalidImageSize {
+            expected_pixels,
+            decoded_pixels,
+        } = err
+        {
+            assert_eq!(expected_pixels, 56);
+            assert_eq!(decoded_pixels, 48);
+        } else {
+            unreachable!();
+        }
     }
 }
 
 #[test]
-fn decode_with_invalid_y_hot_value() {
+fn decode_partial() {
+    let reader = File::open("tests/data/16x14.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let expected = Decoder::new(reader).unwrap().decode_to_vec().unwrap();
+
+    for len in [0, 1, 7, 8, 15, 16, 17, 100, 224, 225, 1024] {
+        let reader = File::open("tests/data/16x14.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let mut buf = vec![u8::MAX; len];
+        let written = decoder.decode_partial(&mut buf).unwrap();
+        assert_eq!(written, len.min(224));
+        assert_eq!(buf[..written], expected[..written]);
+        assert!(buf[written..].iter().all(|&p| p == u8::MAX));
+    }
+
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            #define image_x_hot 4
-            #define image_y_hot 4294967296
             static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+                0x00, 0x1C, 0x24,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
-                .unwrap()
-                .kind(),
-            &IntErrorKind::PosOverflow
-        );
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        let mut buf = [u8::MAX; 56];
+        assert_eq!(decoder.decode_partial(&mut buf).unwrap(), 24);
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            #define image_x_hot 4
-            #define image_y_hot -1
             static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0xFF, 0xFF,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
-                .unwrap()
-                .kind(),
-            &IntErrorKind::InvalidDigit
-        );
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        let mut buf = [u8::MAX; 72];
+        assert_eq!(decoder.decode_partial(&mut buf).unwrap(), 56);
+        assert_eq!(buf[56..], [u8::MAX; 16]);
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            #define image_x_hot 4
-            #define image_y_hot a
             static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0xGG,
             };
         "};
-        let buf = Cursor::new(image);
-        let err = Decoder::new(buf).unwrap_err();
-        assert_eq!(
-            err.source()
-                .unwrap()
-                .downcast_ref::<ParseIntError>()
-                .unwrap()
-                .kind(),
-            &IntErrorKind::InvalidDigit
-        );
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        let mut buf = [u8::MAX; 8];
+        let err = decoder.decode_partial(&mut buf).unwrap_err();
+        assert!(matches!(err, Error::ParseInt(_)));
+    }
+}
+
+#[test]
+fn decode_with_expected_name() {
+    let reader = File::open("tests/data/name.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let name = Decoder::new(reader).unwrap().name().to_owned();
+
+    let reader = File::open("tests/data/name.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new_with_name(reader, &name).unwrap();
+    assert_eq!(decoder.name(), name);
+
+    let reader = File::open("tests/data/name.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let err = Decoder::new_with_name(reader, "image").unwrap_err();
+    if let Error::UnexpectedName { expected, found } = err {
+        assert_eq!(expected, "image");
+        assert_eq!(found, name);
+    } else {
+        unreachable!();
+    }
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char cursor_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        };
+    "};
+    let err = Decoder::new_with_name(Cursor::new(image), "image").unwrap_err();
+    assert!(err.is_invalid_header());
+}
+
+#[test]
+fn decode_with_limits() {
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        };
+    "};
+
+    {
+        let limits = Limits {
+            max_width: 8,
+            max_height: 7,
+            max_pixels: 56,
+        };
+        let decoder = Decoder::with_limits(Cursor::new(image), limits).unwrap();
+        assert!(decoder.decode_to_vec().is_ok());
+    }
+    {
+        let limits = Limits {
+            max_width: 7,
+            ..Default::default()
+        };
+        let err = Decoder::with_limits(Cursor::new(image), limits).unwrap_err();
+        assert!(matches!(err, Error::LimitsExceeded));
+    }
+    {
+        let limits = Limits {
+            max_height: 6,
+            ..Default::default()
+        };
+        let err = Decoder::with_limits(Cursor::new(image), limits).unwrap_err();
+        assert!(matches!(err, Error::LimitsExceeded));
+    }
+    {
+        let limits = Limits {
+            max_pixels: 55,
+            ..Default::default()
+        };
+        let err = Decoder::with_limits(Cursor::new(image), limits).unwrap_err();
+        assert!(matches!(err, Error::LimitsExceeded));
+    }
+}
+
+#[test]
+fn pixel_count() {
+    let reader = File::open("tests/data/16x14.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.pixel_count(), Some(224));
+    assert_eq!(decoder.required_buffer_len(), Some(224));
+
+    let image = indoc! {"
+        #define image_width 65536
+        #define image_height 65536
+        static unsigned char image_bits[] = {
+        };
+    "};
+    let decoder = Decoder::with_limits(Cursor::new(image), Limits::no_limits()).unwrap();
+    if cfg!(target_pointer_width = "64") {
+        assert_eq!(decoder.pixel_count(), Some(1 << 32));
+        assert_eq!(decoder.required_buffer_len(), Some(1 << 32));
+    } else {
+        assert!(decoder.pixel_count().is_none());
+        assert!(decoder.required_buffer_len().is_none());
+        assert!(decoder.decode_to_vec().unwrap_err().is_image_too_large());
     }
 }
 
 #[test]
-fn decode_with_invalid_hex_byte() {
+fn bits_per_pixel() {
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.bits_per_pixel(), 1);
+}
+
+#[test]
+fn format() {
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.format(), Format::X11);
+
+    let reader = File::open("tests/data/x10.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.format(), Format::X10);
+}
+
+#[test]
+fn decode_x10() {
+    {
+        let reader = File::open("tests/data/x10.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        assert_eq!(decoder.width(), 8);
+        assert_eq!(decoder.height(), 7);
+        let buf = decoder.decode_to_vec().unwrap();
+
+        let reader = File::open("tests/data/basic.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let expected = Decoder::new(reader).unwrap().decode_to_vec().unwrap();
+        assert_eq!(buf, expected);
+    }
+    {
+        let reader = File::open("tests/data/x10.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let buf = decoder.decode_packed().unwrap();
+        assert_eq!(*buf, *b"\x00\x1C\x24\x1C\x24\x1C\x00");
+    }
     {
         let image = indoc! {"
-            #define image_width 8
-            #define image_height 7
-            static unsigned char image_bits[] = {
-                \u{1F980}, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            #define image_width 20
+            #define image_height 2
+            static short image_bits[] = {
+                0x8001, 0x0008, 0x0180, 0x000F,
             };
         "};
-        let image = Cursor::new(image);
-        let decoder = Decoder::new(image).unwrap();
-        let mut buf = [u8::default(); 56];
-        let err = decoder.decode(&mut buf).unwrap_err();
-        if let Error::InvalidHexByte(value) = err {
-            assert_eq!(value, "\u{1F980}");
-        } else {
-            unreachable!();
-        }
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        assert_eq!(decoder.format(), Format::X10);
+        let buf = decoder.decode_packed().unwrap();
+        assert_eq!(*buf, *b"\x01\x80\x08\x80\x01\x0F");
     }
     {
         let image = indoc! {"
-            #define image_width 8
-            #define image_height 7
-            static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 1c, 0x24, 0x1C, 0x00,
+            #define image_width 20
+            #define image_height 2
+            static unsigned short image_bits[] = {
+                0x8001, 0x0008,
+                0x0180,
             };
         "};
-        let image = Cursor::new(image);
-        let decoder = Decoder::new(image).unwrap();
-        let mut buf = [u8::default(); 56];
-        let err = decoder.decode(&mut buf).unwrap_err();
-        if let Error::InvalidHexByte(value) = err {
-            assert_eq!(value, "1c");
+        let decoder = Decoder::new(Cursor::new(image))
+            .unwrap()
+            .verify_row_length(true);
+        let err = decoder.decode_to_vec().unwrap_err();
+        if let Error::RowLengthMismatch {
+            row,
+            expected,
+            found,
+        } = err
+        {
+            assert_eq!(row, 1);
+            assert_eq!(expected, 2);
+            assert_eq!(found, 1);
         } else {
             unreachable!();
         }
@@ -1190,16 +3502,15 @@ fn decode_with_invalid_hex_byte() {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0b00,
+            static unsigned short image_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
             };
         "};
-        let image = Cursor::new(image);
-        let decoder = Decoder::new(image).unwrap();
-        let mut buf = [u8::default(); 56];
-        let err = decoder.decode(&mut buf).unwrap_err();
-        if let Error::InvalidHexByte(value) = err {
-            assert_eq!(value, "0b00");
+        let decoder = Decoder::new(Cursor::new(image)).unwrap();
+        let err = decoder.decode_to_vec().unwrap_err();
+        if let Error::InvalidHexByte { value, offset } = err {
+            assert_eq!(value, "0x00");
+            assert_eq!(offset, 88);
         } else {
             unreachable!();
         }
@@ -1207,84 +3518,575 @@ fn decode_with_invalid_hex_byte() {
 }
 
 #[test]
-fn decode_with_invalid_termination() {
+fn decode_with_stream_array() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/16x14_minified.xbm",
+        "tests/data/array_comment.xbm",
+        "tests/data/basic_lower_hex.xbm",
+        "tests/data/basic_minified.xbm",
+        "tests/data/basic_single_line.xbm",
+        "tests/data/basic_upper_prefix.xbm",
+        "tests/data/brace_on_next_line.xbm",
+        "tests/data/brace_on_next_line_with_comment.xbm",
+        "tests/data/crc32.xbm",
+        "tests/data/qr_code.xbm",
+        "tests/data/termination_with_comma.xbm",
+        "tests/data/termination_with_space.xbm",
+        "tests/data/termination_without_comma.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14_minified.xbm",
+        "tests/data/x10.xbm",
+    ] {
+        let expected = Decoder::new(File::open(path).map(BufReader::new).unwrap())
+            .unwrap()
+            .decode_to_vec()
+            .unwrap();
+        // Use a tiny buffer so that the tokens and the comments straddle the
+        // boundaries of the buffer.
+        for capacity in [1, 3, 8192] {
+            let reader = File::open(path)
+                .map(|f| BufReader::with_capacity(capacity, f))
+                .unwrap();
+            let decoder = Decoder::new(reader)
+                .unwrap()
+                .verify_crc32(true)
+                .stream_array(true);
+            assert_eq!(decoder.decode_to_vec().unwrap(), expected, "{path}");
+        }
+    }
+}
+
+#[test]
+fn decode_with_stream_array_and_comments() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, /* ...*/ 0x1C, /*/ } */ 0x24, // 0x00, };
+            0x1C,/**/0x24,
+            0x1C, 0x00, }; /* end */
+    "};
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    assert_eq!(decoder.decode_to_vec().unwrap(), expected);
+    let decoder = Decoder::new(Cursor::new(image)).unwrap().stream_array(true);
+    assert_eq!(decoder.decode_to_vec().unwrap(), expected);
+}
+
+#[test]
+fn decode_invalid_image_with_stream_array() {
+    for (array, expected) in [
+        (
+            "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,\n};",
+            Error::InvalidImageSize {
+                expected_pixels: 56,
+                decoded_pixels: 48,
+            },
+        ),
+        (
+            "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,\n",
+            Error::UnexpectedEof,
+        ),
+        (
+            "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,\n}",
+            Error::InvalidTermination,
+        ),
+        (
+            "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x0,\n};",
+            Error::InvalidHexByte {
+                value: String::from("0x0"),
+                offset: 123,
+            },
+        ),
+        (
+            "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C 0x00,\n};",
+            Error::InvalidHexByte {
+                value: String::from("0x1C 0x00"),
+                offset: 117,
+            },
+        ),
+        (
+            "0x00, 0x1C, 0x24, 0x1C, 0x24,, 0x1C, 0x00,\n};",
+            Error::InvalidHexByte {
+                value: String::new(),
+                offset: 116,
+            },
+        ),
+    ] {
+        let image = format!(
+            "#define image_width 8\n#define image_height 7\nstatic unsigned char \
+             image_bits[] = {{\n    {array}\n"
+        );
+        let line_err = Decoder::new(Cursor::new(&image))
+            .unwrap()
+            .decode_to_vec()
+            .unwrap_err();
+        let err = Decoder::new(Cursor::new(&image))
+            .unwrap()
+            .stream_array(true)
+            .decode_to_vec()
+            .unwrap_err();
+        assert_eq!(err.to_string(), expected.to_string(), "{array:?}");
+        assert_eq!(line_err.to_string(), expected.to_string(), "{array:?}");
+    }
+
+    // Only whitespace and comments are allowed after the termination string.
+    for (array, expected) in [
+        (
+            "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,\n}; /* end */",
+            None,
+        ),
+        (
+            "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,\n};\n;",
+            Some(Error::InvalidTermination),
+        ),
+        // A token is rejected without buffering it entirely.
+        (
+            "0x000000000000000000000000",
+            Some(Error::InvalidHexByte {
+                value: String::from("0x000000000000000"),
+                offset: 87,
+            }),
+        ),
+    ] {
+        let image = format!(
+            "#define image_width 8\n#define image_height 7\nstatic unsigned char \
+             image_bits[] = {{\n    {array}\n"
+        );
+        let result = Decoder::new(Cursor::new(image))
+            .unwrap()
+            .stream_array(true)
+            .decode_to_vec();
+        assert_eq!(
+            result.err().map(|e| e.to_string()),
+            expected.map(|e| e.to_string()),
+            "{array:?}"
+        );
+    }
+}
+
+#[test]
+fn decode_long_single_line_with_stream_array() {
+    let width = 8 * 1024;
+    let height = 64;
+    let array = "0x00, 0x1C, 0x24, 0xFF,".repeat(width / 32 * height);
+    let image = format!(
+        "#define image_width {width}\n#define image_height {height}\n\
+         static unsigned char image_bits[] = {{{array}}};\n"
+    );
+
+    let expected = Decoder::new(Cursor::new(&image))
+        .unwrap()
+        .decode_packed()
+        .unwrap();
+    let decoder = Decoder::new(Cursor::new(&image))
+        .unwrap()
+        .stream_array(true);
+    assert_eq!(decoder.decode_packed().unwrap(), expected);
+    assert_eq!(expected.len(), width / 8 * height);
+}
+
+#[test]
+fn decode_from_huge_dimensions() {
+    let image = indoc! {"
+        #define image_width 4294967295
+        #define image_height 4294967295
+        static unsigned char image_bits[] = {
+            0x00,
+        };
+    "};
+
+    let err = Decoder::new(Cursor::new(image)).unwrap_err();
+    assert!(matches!(err, Error::LimitsExceeded));
+
+    let decoder = Decoder::with_limits(Cursor::new(image), Limits::no_limits()).unwrap();
+    assert_eq!(decoder.width(), u32::MAX);
+    assert_eq!(decoder.height(), u32::MAX);
+}
+
+#[test]
+fn decode_from_huge_dimensions_with_surplus_bytes() {
+    let image = indoc! {"
+        #define image_width 4294967295
+        #define image_height 4294967295
+        static unsigned char image_bits[] = {
+            0x00, 0x00,
+        };
+    "};
+
+    let decoder = Decoder::with_limits(Cursor::new(image), Limits::no_limits()).unwrap();
+    let err = decoder.skip_image().unwrap_err();
+    if cfg!(target_pointer_width = "64") {
+        assert!(matches!(
+            err,
+            Error::InvalidImageSize {
+                expected_pixels,
+                decoded_pixels: 16
+            } if u64::try_from(expected_pixels) == Ok(u64::from(u32::MAX).pow(2))
+        ));
+    } else {
+        assert!(err.is_image_too_large());
+    }
+
+    let image = indoc! {"
+        #define image_width 4294967295
+        #define image_height 1
+        static unsigned char image_bits[] = {
+            0x00, 0x00,
+        };
+    "};
+    let decoder = Decoder::with_limits(Cursor::new(image), Limits::no_limits()).unwrap();
+    let err = decoder.skip_image().unwrap_err();
+    assert!(matches!(
+        err,
+        Error::InvalidImageSize {
+            expected_pixels: 0xFFFF_FFFF,
+            decoded_pixels: 16
+        }
+    ));
+
+    let image = indoc! {"
+        #define image_width 1
+        #define image_height 1
+        static unsigned char image_bits[] = {
+            0x00, 0x00, 0x00,
+        };
+    "};
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    let err = decoder.decode_packed().unwrap_err();
+    assert!(matches!(
+        err,
+        Error::InvalidImageSize {
+            expected_pixels: 1,
+            decoded_pixels: 3
+        }
+    ));
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    let mut buf = [u8::default(); 1];
+    let err = decoder.decode(&mut buf).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::InvalidImageSize {
+            expected_pixels: 1,
+            decoded_pixels: 3
+        }
+    ));
+}
+
+#[test]
+fn decode_transposed() {
+    // "I" (14x12)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+    let mut expected = [u8::default(); 168];
+    for y in 0..12 {
+        for x in 0..14 {
+            expected[x * 12 + y] = pixels[y * 14 + x];
+        }
+    }
+
+    let reader = File::open("tests/data/width_14.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    let mut buf = [u8::default(); 168];
+    decoder.decode_transposed(&mut buf).unwrap();
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn decode_transposed_with_invalid_image_size() {
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0x00,
+        };
+    "};
+
+    let decoder = Decoder::new(Cursor::new(image)).unwrap();
+    let mut buf = [u8::default(); 56];
+    let err = decoder.decode_transposed(&mut buf).unwrap_err();
+    if let Error::InvalidImageSize {
+        expected_pixels,
+        decoded_pixels,
+    } = err
     {
-        let image = indoc! {"
-            #define image_width 8
-            #define image_height 7
-            static unsigned char image_bits[] = {
-                0x00, 0x1C }; 0x24, 0x1C, 0x24, 0x1C, 0x00,
-        "};
-        let image = Cursor::new(image);
-        let decoder = Decoder::new(image).unwrap();
-        let mut buf = [u8::default(); 56];
-        let err = decoder.decode(&mut buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidTermination));
+        assert_eq!(expected_pixels, 56);
+        assert_eq!(decoded_pixels, 64);
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn decode_from_invalid_hex_byte_value() {
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0xgg, 0x24, 0x1C, 0x00,
+        };
+    "};
+    let image = Cursor::new(image);
+    let decoder = Decoder::new(image).unwrap();
+    let mut buf = [u8::default(); 56];
+    let err = decoder.decode(&mut buf).unwrap_err();
+    assert_eq!(
+        err.source()
+            .unwrap()
+            .downcast_ref::<ParseIntError>()
+            .unwrap()
+            .kind(),
+        &IntErrorKind::InvalidDigit
+    );
+}
+
+#[test]
+#[should_panic(expected = "`buf` and the image dimensions are different")]
+fn decode_with_invalid_buffer() {
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    let mut buf = [];
+    let _ = decoder.decode(&mut buf);
+}
+
+#[test]
+fn decode_frames() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let reader = File::open("tests/data/frames.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let frames = Decoder::decode_frames(reader).unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].0.name(), "frame0");
+    assert_eq!(frames[0].0.width(), 8);
+    assert_eq!(frames[0].0.height(), 7);
+    assert_eq!(frames[0].1, expected);
+    assert_eq!(frames[1].0.name(), "frame1");
+    assert!(frames[1].1.iter().zip(expected).all(|(a, b)| a != b));
+
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let frames = Decoder::decode_frames(reader).unwrap();
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].1, expected);
+
+    assert!(Decoder::decode_frames(Cursor::new("\n\n"))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn decode_frames_with_invalid_image() {
+    let image = indoc! {"
+        #define frame0_width 8
+        #define frame0_height 7
+        static unsigned char frame0_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        };
+        #define frame1_width 8
+        #define frame1_height 7
+        static unsigned char frame1_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+    "};
+    let err = Decoder::decode_frames(Cursor::new(image)).unwrap_err();
+    assert!(err.is_unexpected_eof());
+
+    let image = indoc! {"
+        #define frame0_width 8
+        #define frame0_height 7
+        static unsigned char frame0_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        };
+        #define frame1_width 8
+    "};
+    let err = Decoder::decode_frames(Cursor::new(image)).unwrap_err();
+    assert!(err.is_invalid_header());
+}
+
+#[test]
+fn read_from_path() {
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x01\x00\x00\x01\x00\x00\
+                     \x00\x00\x01\x01\x01\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let (header, pixels) = xbm::decode::read_from_path("tests/data/basic.xbm").unwrap();
+    assert_eq!(header.name(), "image");
+    assert_eq!(header.width(), 8);
+    assert_eq!(header.height(), 7);
+    assert!(header.x_hot().is_none());
+    assert!(header.y_hot().is_none());
+    assert_eq!(pixels, expected);
+
+    let err = xbm::decode::read_from_path("tests/data/nonexistent.xbm").unwrap_err();
+    assert!(err.is_io());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn decode_parallel() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/basic_minified.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14_minified.xbm",
+        "tests/data/qr_code.xbm",
+        "tests/data/x10.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let expected = Decoder::new(reader).unwrap().decode_to_vec().unwrap();
+
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let mut buf = vec![u8::default(); expected.len()];
+        decoder.decode_parallel(&mut buf).unwrap();
+        assert_eq!(buf, expected);
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn decode_parallel_matches_decode() {
+    use std::fs;
+
+    fn decode(image: &[u8], parallel: bool) -> Option<String> {
+        let decoder = Decoder::new(Cursor::new(image)).ok()?.verify_crc32(true);
+        let mut buf = vec![u8::default(); decoder.required_buffer_len()?];
+        let result = if parallel {
+            decoder.decode_parallel(&mut buf)
+        } else {
+            decoder.decode(&mut buf)
+        };
+        // The content of `buf` is unspecified if an error occurs.
+        Some(result.map_or_else(|err| format!("{err:?}"), |()| format!("{buf:?}")))
     }
-    {
-        let image = indoc! {"
-            #define image_width 8
-            #define image_height 7
-            static unsigned char image_bits[] = {
-                0x00, 0x1C};0x24, 0x1C, 0x24, 0x1C, 0x00,
-        "};
-        let image = Cursor::new(image);
-        let decoder = Decoder::new(image).unwrap();
-        let mut buf = [u8::default(); 56];
-        let err = decoder.decode(&mut buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidTermination));
+
+    let mut images = fs::read_dir("tests/data")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|e| e == "xbm"))
+        .map(|path| fs::read(path).unwrap())
+        .collect::<Vec<_>>();
+    assert!(!images.is_empty());
+    for body in [
+        // A line without a trailing comma.
+        "0x00, 0x1C, 0x24\n    0x1C, 0x24, 0x1C, 0x00,\n};",
+        // An unterminated comment before the termination string.
+        "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, /* rows\n};",
+        // A comment spanning lines.
+        "0x00, 0x1C, /* rows\n    */ 0x24, 0x1C, 0x24, 0x1C, 0x00,\n};",
+        // Junk after the termination string.
+        "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,\n};\n0x00",
+        // A hex byte split by a newline.
+        "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x0\n0,\n};",
+        // An invalid hex byte after an invalid line.
+        "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,\n};\n0xZZ,\n};",
+        "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,\n};",
+        "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0x00,\n};",
+        "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,",
+        "0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,\n}",
+    ] {
+        images.push(
+            format!(
+                "#define image_width 8\n#define image_height 7\n\
+                 static unsigned char image_bits[] = {{\n    {body}\n"
+            )
+            .into_bytes(),
+        );
     }
-    {
-        let image = indoc! {"
-            #define image_width 8
-            #define image_height 7
-            static unsigned char image_bits[] = {
-                0x00, 0x1C, }; 0x24, 0x1C, 0x24, 0x1C, 0x00,
-        "};
-        let image = Cursor::new(image);
-        let decoder = Decoder::new(image).unwrap();
-        let mut buf = [u8::default(); 56];
-        let err = decoder.decode(&mut buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidTermination));
+    for image in images {
+        let expected = decode(&image, false);
+        assert_eq!(
+            decode(&image, true),
+            expected,
+            "{}",
+            String::from_utf8_lossy(&image)
+        );
     }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn decode_parallel_with_invalid_image() {
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
             static unsigned char image_bits[] = {
-                0x00, 0x1C,};0x24, 0x1C, 0x24, 0x1C, 0x00,
+                0x00, 0x1C, 0x24, 1c, 0x24, 0x1C, 0b00,
+            };
         "};
         let image = Cursor::new(image);
         let decoder = Decoder::new(image).unwrap();
         let mut buf = [u8::default(); 56];
-        let err = decoder.decode(&mut buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidTermination));
+        let err = decoder.decode_parallel(&mut buf).unwrap_err();
+        if let Error::InvalidHexByte { value, offset } = err {
+            assert_eq!(value, "1c");
+            assert_eq!(offset, 105);
+        } else {
+            unreachable!();
+        }
     }
-
     {
-        // "B" (8x7)
-        let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
-                         \x00\x00\x01\x01\x01\x00\x00\x00\
-                         \x00\x00\x01\x00\x00\x01\x00\x00\
-                         \x00\x00\x01\x01\x01\x00\x00\x00\
-                         \x00\x00\x01\x00\x00\x01\x00\x00\
-                         \x00\x00\x01\x01\x01\x00\x00\x00\
-                         \x00\x00\x00\x00\x00\x00\x00\x00";
-
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
-            static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
-            };\x20
+            static unsigned char image_bits[] = { /* row 0 */
+                0x00, /* 1 */ 0x1C, 0x24, // 3
+                0x1C, 0x24,
+
+                0x1C, 0x0zz,
+            };
         "};
         let image = Cursor::new(image);
         let decoder = Decoder::new(image).unwrap();
         let mut buf = [u8::default(); 56];
-        decoder.decode(&mut buf).unwrap();
-        assert_eq!(buf, *expected);
+        let err = decoder.decode_parallel(&mut buf).unwrap_err();
+        if let Error::InvalidHexByte { value, offset } = err {
+            assert_eq!(value, "0x0zz");
+            assert_eq!(offset, 157);
+        } else {
+            unreachable!();
+        }
     }
-
     {
         let image = indoc! {"
             #define image_width 8
@@ -1295,99 +4097,265 @@ fn decode_with_invalid_termination() {
         let image = Cursor::new(image);
         let decoder = Decoder::new(image).unwrap();
         let mut buf = [u8::default(); 56];
-        let err = decoder.decode(&mut buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidTermination));
+        let err = decoder.decode_parallel(&mut buf).unwrap_err();
+        assert!(matches!(err, Error::UnexpectedEof));
     }
     {
         let image = indoc! {"
             #define image_width 8
             #define image_height 7
             static unsigned char image_bits[] = {
-                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
+            };
         "};
         let image = Cursor::new(image);
         let decoder = Decoder::new(image).unwrap();
         let mut buf = [u8::default(); 56];
-        let err = decoder.decode(&mut buf).unwrap_err();
-        assert!(matches!(err, Error::InvalidTermination));
+        let err = decoder.decode_parallel(&mut buf).unwrap_err();
+        if let Error::InvalidImageSize {
+            expected_pixels,
+            decoded_pixels,
+        } = err
+        {
+            assert_eq!(expected_pixels, 56);
+            assert_eq!(decoded_pixels, 48);
+        } else {
+            unreachable!();
+        }
     }
 }
 
+#[cfg(feature = "image")]
 #[test]
-fn decode_with_invalid_image_size() {
-    let image = indoc! {"
-        #define image_width 8
-        #define image_height 7
-        static unsigned char image_bits[] = {
-            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C,
-        };
-    "};
-    let image = Cursor::new(image);
-    let decoder = Decoder::new(image).unwrap();
+fn image_decoder() {
+    use image::{ColorType, ExtendedColorType, ImageDecoder};
+
+    // "B" (8x7)
+    let expected = b"\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\
+                     \xFF\xFF\x00\x00\x00\xFF\xFF\xFF\
+                     \xFF\xFF\x00\xFF\xFF\x00\xFF\xFF\
+                     \xFF\xFF\x00\x00\x00\xFF\xFF\xFF\
+                     \xFF\xFF\x00\xFF\xFF\x00\xFF\xFF\
+                     \xFF\xFF\x00\x00\x00\xFF\xFF\xFF\
+                     \xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF";
+
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let mut decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.dimensions(), (8, 7));
+    assert_eq!(decoder.color_type(), ColorType::L8);
+    assert_eq!(decoder.original_color_type(), ExtendedColorType::L1);
+    assert_eq!(decoder.icc_profile().unwrap(), None);
+    assert_eq!(decoder.total_bytes(), 56);
     let mut buf = [u8::default(); 56];
-    let err = decoder.decode(&mut buf).unwrap_err();
-    if let Error::InvalidImageSize(size) = err {
-        assert_eq!(size, 48);
-    } else {
-        unreachable!();
-    }
+    decoder.read_image(&mut buf).unwrap();
+    assert_eq!(buf, *expected);
 }
 
+#[cfg(feature = "image")]
 #[test]
-#[should_panic(expected = "range end index 64 out of range for slice of length 56")]
-fn decode_from_too_large_image() {
-    let image = indoc! {"
-        #define image_width 8
-        #define image_height 7
-        static unsigned char image_bits[] = {
-            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0x00,
-        };
-    "};
-    let image = Cursor::new(image);
-    let decoder = Decoder::new(image).unwrap();
-    let mut buf = [u8::default(); 56];
-    let _ = decoder.decode(&mut buf);
+fn image_decoder_with_width_not_multiple_of_8() {
+    use image::ImageDecoder;
+
+    // "I" (7x6)
+    let expected = b"\xFF\xFF\xFF\xFF\xFF\xFF\xFF\
+                     \xFF\xFF\x00\x00\x00\xFF\xFF\
+                     \xFF\xFF\xFF\x00\xFF\xFF\xFF\
+                     \xFF\xFF\xFF\x00\xFF\xFF\xFF\
+                     \xFF\xFF\x00\x00\x00\xFF\xFF\
+                     \xFF\xFF\xFF\xFF\xFF\xFF\xFF";
+
+    let reader = File::open("tests/data/width_7.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let mut decoder = Decoder::new(reader).unwrap();
+    assert_eq!(decoder.dimensions(), (7, 6));
+    assert_eq!(decoder.icc_profile().unwrap(), None);
+    assert_eq!(decoder.total_bytes(), 7 * 6);
+    let mut buf = [u8::default(); 42];
+    decoder.read_image(&mut buf).unwrap();
+    assert_eq!(buf, *expected);
 }
 
 #[test]
-fn decode_from_invalid_hex_byte_value() {
+fn decode_with_bit_order() {
     let image = indoc! {"
         #define image_width 8
         #define image_height 7
+        #define image_crc32 0x5C4051F3
         static unsigned char image_bits[] = {
-            0x00, 0x1C, 0x24, 0xgg, 0x24, 0x1C, 0x00,
+            0x00, 0x38, 0x24, 0x38, 0x24, 0x38, 0x00,
         };
     "};
-    let image = Cursor::new(image);
-    let decoder = Decoder::new(image).unwrap();
-    let mut buf = [u8::default(); 56];
-    let err = decoder.decode(&mut buf).unwrap_err();
+    let expected = Decoder::from_bytes(include_bytes!("data/basic.xbm"))
+        .unwrap()
+        .decode_to_vec()
+        .unwrap();
+
+    let decoder = Decoder::new(Cursor::new(image))
+        .unwrap()
+        .verify_crc32(true)
+        .bit_order(BitOrder::Msb);
+    assert_eq!(decoder.decode_to_vec().unwrap(), expected);
+
+    let decoder = Decoder::new(Cursor::new(image))
+        .unwrap()
+        .stream_array(true)
+        .bit_order(BitOrder::Msb);
+    assert_eq!(decoder.decode_to_vec().unwrap(), expected);
+
+    let decoder = Decoder::new(Cursor::new(image))
+        .unwrap()
+        .bit_order(BitOrder::Msb);
     assert_eq!(
-        err.source()
-            .unwrap()
-            .downcast_ref::<ParseIntError>()
-            .unwrap()
-            .kind(),
-        &IntErrorKind::InvalidDigit
+        decoder.decode_packed().unwrap(),
+        b"\x00\x1C\x24\x1C\x24\x1C\x00".as_slice()
     );
+
+    let decoder = Decoder::new(Cursor::new(image))
+        .unwrap()
+        .bit_order(BitOrder::Lsb);
+    assert_ne!(decoder.decode_to_vec().unwrap(), expected);
+
+    #[cfg(feature = "rayon")]
+    {
+        let decoder = Decoder::new(Cursor::new(image))
+            .unwrap()
+            .verify_crc32(true)
+            .bit_order(BitOrder::Msb);
+        let mut buf = [u8::default(); 56];
+        decoder.decode_parallel(&mut buf).unwrap();
+        assert_eq!(buf.as_slice(), expected);
+    }
 }
 
 #[test]
-#[should_panic(expected = "`buf` and the image dimensions are different")]
-fn decode_with_invalid_buffer() {
+fn decode_inverted() {
+    // "B" (8x7)
+    let expected = b"\x01\x01\x01\x01\x01\x01\x01\x01\
+                     \x01\x01\x00\x00\x00\x01\x01\x01\
+                     \x01\x01\x00\x01\x01\x00\x01\x01\
+                     \x01\x01\x00\x00\x00\x01\x01\x01\
+                     \x01\x01\x00\x01\x01\x00\x01\x01\
+                     \x01\x01\x00\x00\x00\x01\x01\x01\
+                     \x01\x01\x01\x01\x01\x01\x01\x01";
+
     let reader = File::open("tests/data/basic.xbm")
         .map(BufReader::new)
         .unwrap();
-    let decoder = Decoder::new(reader).unwrap();
-    let mut buf = [];
-    let _ = decoder.decode(&mut buf);
+    let decoder = Decoder::new(reader).unwrap().invert(true);
+    let mut buf = [u8::default(); 56];
+    decoder.decode(&mut buf).unwrap();
+    assert_eq!(buf, *expected);
+
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap().invert(true);
+    let mut buf = [u8::default(); 56];
+    decoder.decode_transposed(&mut buf).unwrap();
+    for y in 0..7 {
+        for x in 0..8 {
+            assert_eq!(buf[x * 7 + y], expected[y * 8 + x]);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    {
+        let reader = File::open("tests/data/basic.xbm")
+            .map(BufReader::new)
+            .unwrap();
+        let decoder = Decoder::new(reader).unwrap().invert(true);
+        let mut buf = [u8::default(); 56];
+        decoder.decode_parallel(&mut buf).unwrap();
+        assert_eq!(buf, *expected);
+    }
 }
 
 #[cfg(feature = "image")]
 #[test]
-fn image_decoder() {
-    use image::{ColorType, ExtendedColorType, ImageDecoder};
+fn image_decoder_inverted() {
+    use image::{ColorType, DynamicImage, ImageDecoder};
+
+    // "B" (8x7)
+    let expected = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                     \x00\x00\xFF\xFF\xFF\x00\x00\x00\
+                     \x00\x00\xFF\x00\x00\xFF\x00\x00\
+                     \x00\x00\xFF\xFF\xFF\x00\x00\x00\
+                     \x00\x00\xFF\x00\x00\xFF\x00\x00\
+                     \x00\x00\xFF\xFF\xFF\x00\x00\x00\
+                     \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap().invert(true);
+    assert_eq!(decoder.color_type(), ColorType::L8);
+    let image = DynamicImage::from_decoder(decoder).unwrap();
+    assert_eq!(image.as_bytes(), expected);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn image_decoder_with_limits() {
+    use image::{error::LimitErrorKind, DynamicImage, ImageDecoder, ImageError, Limits};
 
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let mut decoder = Decoder::new(reader).unwrap();
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(8);
+    limits.max_image_height = Some(7);
+    limits.max_alloc = Some(56);
+    decoder.set_limits(limits).unwrap();
+    let image = DynamicImage::from_decoder(decoder).unwrap();
+    assert_eq!(image.as_bytes().len(), 56);
+
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let mut decoder = Decoder::new(reader).unwrap();
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(7);
+    let err = decoder.set_limits(limits).unwrap_err();
+    if let ImageError::Limits(err) = err {
+        assert_eq!(err.kind(), LimitErrorKind::DimensionError);
+    } else {
+        unreachable!();
+    }
+
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let mut decoder = Decoder::new(reader).unwrap();
+    let mut limits = Limits::default();
+    limits.max_image_height = Some(6);
+    let err = decoder.set_limits(limits).unwrap_err();
+    if let ImageError::Limits(err) = err {
+        assert_eq!(err.kind(), LimitErrorKind::DimensionError);
+    } else {
+        unreachable!();
+    }
+
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let mut decoder = Decoder::new(reader).unwrap();
+    let mut limits = Limits::default();
+    limits.max_alloc = Some(55);
+    let err = decoder.set_limits(limits).unwrap_err();
+    if let ImageError::Limits(err) = err {
+        assert_eq!(err.kind(), LimitErrorKind::InsufficientMemory);
+    } else {
+        unreachable!();
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn into_gray_image() {
     // "B" (8x7)
     let expected = b"\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\
                      \xFF\xFF\x00\x00\x00\xFF\xFF\xFF\
@@ -1400,15 +4368,56 @@ fn image_decoder() {
     let reader = File::open("tests/data/basic.xbm")
         .map(BufReader::new)
         .unwrap();
-    let mut decoder = Decoder::new(reader).unwrap();
-    assert_eq!(decoder.dimensions(), (8, 7));
-    assert_eq!(decoder.color_type(), ColorType::L8);
-    assert_eq!(decoder.original_color_type(), ExtendedColorType::L1);
-    assert_eq!(decoder.icc_profile().unwrap(), None);
-    assert_eq!(decoder.total_bytes(), 56);
-    let mut buf = [u8::default(); 56];
-    decoder.read_image(&mut buf).unwrap();
-    assert_eq!(buf, *expected);
+    let decoder = Decoder::new(reader).unwrap();
+    let image = decoder.into_gray_image().unwrap();
+    assert_eq!(image.dimensions(), (8, 7));
+    assert_eq!(image.as_raw(), expected);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn decode_rgba() {
+    for path in ["tests/data/basic.xbm", "tests/data/qr_code.xbm"] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let expected = image::DynamicImage::from(decoder.into_gray_image().unwrap()).into_rgba8();
+
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let image = decoder.decode_rgba().unwrap();
+        assert_eq!(image, expected);
+    }
+
+    let reader = File::open("tests/data/basic.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap().invert(true);
+    let image = decoder.decode_rgba().unwrap();
+    assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 255]);
+    assert_eq!(image.get_pixel(2, 1).0, [255, 255, 255, 255]);
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn decode_image_frames() {
+    use image::Delay;
+
+    let delay = Delay::from_numer_denom_ms(100, 1);
+    let reader = File::open("tests/data/frames.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let frames = Decoder::decode_image_frames(reader, delay).unwrap();
+    assert_eq!(frames.len(), 2);
+    for frame in &frames {
+        assert_eq!(frame.delay(), delay);
+        assert_eq!((frame.left(), frame.top()), (0, 0));
+        assert_eq!(frame.buffer().dimensions(), (8, 7));
+    }
+    assert_eq!(frames[0].buffer().get_pixel(0, 0).0, [255, 255, 255, 255]);
+    assert_eq!(frames[1].buffer().get_pixel(0, 0).0, [0, 0, 0, 255]);
+
+    let image = "#define frame0_width 8\n";
+    assert!(Decoder::decode_image_frames(Cursor::new(image), delay).is_err());
 }
 
 #[cfg(feature = "image")]
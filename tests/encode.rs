@@ -3,11 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use std::{
-    io::{ErrorKind, Write},
-    str,
+    env,
+    fs::{self, File},
+    io::{self, BufReader, Cursor, ErrorKind, Write},
+    iter, str,
 };
 
-use xbm::Encoder;
+use indoc::indoc;
+use xbm::{encode::ElementType, BitOrder, Decoder, Encoder};
 
 #[test]
 fn encode() {
@@ -29,6 +32,217 @@ fn encode() {
     );
 }
 
+#[test]
+fn encode_counted() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = Vec::new();
+    let len = Encoder::new(&mut buf)
+        .encode_counted(pixels, "image", 8, 7, None, None)
+        .unwrap();
+    assert_eq!(len, 132);
+    let offset = buf.len();
+    let len = Encoder::new(&mut buf)
+        .emit_crc32(true)
+        .encode_counted(pixels, "image", 8, 7, Some(4), Some(3))
+        .unwrap();
+    assert_eq!(offset + len, buf.len());
+    assert_eq!(
+        str::from_utf8(&buf[..offset]).unwrap(),
+        include_str!("data/basic.xbm")
+    );
+}
+
+#[test]
+fn encode_flushes_writer() {
+    struct Flushed {
+        buf: Vec<u8>,
+        flushed: usize,
+    }
+
+    impl Write for Flushed {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.buf.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushed = self.buf.len();
+            Ok(())
+        }
+    }
+
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut writer = Flushed {
+        buf: Vec::new(),
+        flushed: usize::default(),
+    };
+    Encoder::new(&mut writer)
+        .encode(pixels, "image", 8, 7, None, None)
+        .unwrap();
+    assert_eq!(writer.flushed, 132);
+
+    let mut encoder = Encoder::new(&mut writer)
+        .begin("image", 8, 7, None, None)
+        .unwrap();
+    for row in pixels.chunks(8) {
+        encoder.write_row(row).unwrap();
+    }
+    encoder.finish().unwrap();
+    assert_eq!(writer.flushed, 264);
+}
+
+#[test]
+fn encode_by_ref() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+    let inverted = pixels.iter().map(|p| p ^ 1).collect::<Vec<_>>();
+
+    let mut encoder = Encoder::new(Vec::new()).index_comments(true);
+    encoder
+        .by_ref()
+        .encode(pixels, "frame0", 8, 7, None, None)
+        .unwrap();
+    encoder
+        .by_ref()
+        .encode(&inverted, "frame1", 8, 7, None, None)
+        .unwrap();
+    let buf = encoder.into_inner();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap().matches("/* [0] */").count(),
+        2
+    );
+
+    let frames = Decoder::decode_frames(Cursor::new(buf)).unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].0.name(), "frame0");
+    assert_eq!(frames[0].1, pixels);
+    assert_eq!(frames[1].0.name(), "frame1");
+    assert_eq!(frames[1].1, inverted);
+}
+
+#[test]
+fn encode_many() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+    let inverted = pixels.iter().map(|p| p ^ 1).collect::<Vec<_>>();
+
+    let mut buf = Vec::new();
+    let encoder = Encoder::new(buf.by_ref()).leading_comment("/* frames */");
+    encoder
+        .encode_many(&[
+            ("frame0", pixels, 8, 7, None),
+            ("frame1", &inverted, 8, 7, Some((4, 3))),
+        ])
+        .unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        indoc! {"
+            /* frames */
+            #define frame0_width 8
+            #define frame0_height 7
+            static unsigned char frame0_bits[] = {
+                0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+            };
+
+            #define frame1_width 8
+            #define frame1_height 7
+            #define frame1_x_hot 4
+            #define frame1_y_hot 3
+            static unsigned char frame1_bits[] = {
+                0xFF, 0xE3, 0xDB, 0xE3, 0xDB, 0xE3, 0xFF,
+            };
+        "}
+    );
+
+    let frames = Decoder::decode_frames(Cursor::new(buf)).unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].0.name(), "frame0");
+    assert_eq!(frames[0].1, pixels);
+    assert_eq!(frames[1].0.name(), "frame1");
+    assert_eq!(frames[1].0.x_hot(), Some(4));
+    assert_eq!(frames[1].0.y_hot(), Some(3));
+    assert_eq!(frames[1].1, inverted);
+
+    let mut buf = Vec::new();
+    Encoder::new(buf.by_ref()).encode_many(&[]).unwrap();
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn encode_many_with_invalid_image() {
+    let pixels = [u8::default(); 56];
+
+    let mut buf = Vec::new();
+    let err = Encoder::new(buf.by_ref())
+        .encode_many(&[
+            ("frame0", &pixels, 8, 7, None),
+            ("1frame", &pixels, 8, 7, None),
+            ("frame2", &pixels, 8, 7, None),
+        ])
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let image = str::from_utf8(&buf).unwrap();
+    assert!(image.contains("frame0_bits"));
+    assert!(!image.contains("frame2"));
+
+    let err = Encoder::new(Vec::new())
+        .encode_many(&[("frame0", &[], 8, 0, None)])
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn write_to_path() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let path = env::temp_dir().join("xbm-write_to_path.xbm");
+    xbm::encode::write_to_path(&path, pixels, "image", 8, 7, Some(4), Some(3)).unwrap();
+    let (header, buf) = xbm::decode::read_from_path(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(header.name(), "image");
+    assert_eq!(header.x_hot(), Some(4));
+    assert_eq!(header.y_hot(), Some(3));
+    assert_eq!(buf, pixels);
+
+    let err = xbm::encode::write_to_path(&path, pixels, "0image", 8, 7, None, None).unwrap_err();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
 #[test]
 fn encode_width_name() {
     // "B" (8x7)
@@ -64,36 +278,745 @@ fn encode_16x14() {
                    \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
                    \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
 
-    let mut buf = [u8::default(); 268];
-    let encoder = Encoder::new(buf.as_mut_slice());
-    encoder.encode(pixels, "image", 16, 14, None, None).unwrap();
+    let mut buf = [u8::default(); 268];
+    let encoder = Encoder::new(buf.as_mut_slice());
+    encoder.encode(pixels, "image", 16, 14, None, None).unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        include_str!("data/16x14.xbm")
+    );
+}
+
+#[test]
+fn encode_width_7() {
+    // "I" (7x6)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\
+                   \x00\x00\x00\x01\x00\x00\x00\
+                   \x00\x00\x00\x01\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = [u8::default(); 126];
+    let encoder = Encoder::new(buf.as_mut_slice());
+    encoder.encode(pixels, "image", 7, 6, None, None).unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        include_str!("data/width_7.xbm")
+    );
+}
+
+#[test]
+fn encode_width_14() {
+    // "I" (14x12)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = [u8::default(); 240];
+    let encoder = Encoder::new(buf.as_mut_slice());
+    encoder.encode(pixels, "image", 14, 12, None, None).unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        include_str!("data/width_14.xbm")
+    );
+}
+
+#[test]
+fn encode_with_hotspot() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = [u8::default(); 176];
+    let encoder = Encoder::new(buf.as_mut_slice());
+    encoder
+        .encode(pixels, "image", 8, 7, Some(4), Some(3))
+        .unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        include_str!("data/hotspot.xbm")
+    );
+}
+
+#[test]
+fn encode_nonzero() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let reader = File::open("tests/data/hotspot.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let decoder = Decoder::new(reader).unwrap();
+    let (width, height) = (decoder.nonzero_width(), decoder.nonzero_height());
+    assert_eq!((width.get(), height.get()), (8, 7));
+
+    let mut buf = [u8::default(); 176];
+    let encoder = Encoder::new(buf.as_mut_slice());
+    encoder
+        .encode_nonzero(pixels, "image", width, height, Some(4), Some(3))
+        .unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        include_str!("data/hotspot.xbm")
+    );
+}
+
+#[test]
+fn encode_with_zero_hotspot() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = Vec::new();
+    let encoder = Encoder::new(buf.by_ref());
+    encoder
+        .encode(pixels, "image", 8, 7, Some(0), Some(0))
+        .unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        include_str!("data/zero_hotspot.xbm")
+    );
+
+    let decoder = Decoder::new(Cursor::new(buf)).unwrap();
+    assert_eq!(decoder.x_hot(), Some(0));
+    assert_eq!(decoder.y_hot(), Some(0));
+    assert_eq!(decoder.decode_to_vec().unwrap(), pixels);
+}
+
+#[test]
+fn encode_with_hotspot_pair() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let mut buf = [u8::default(); 176];
+        let encoder = Encoder::new(buf.as_mut_slice());
+        encoder
+            .encode_with_hotspot(pixels, "image", 8, 7, Some((4, 3)))
+            .unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/hotspot.xbm")
+        );
+    }
+    {
+        let mut buf = [u8::default(); 132];
+        let encoder = Encoder::new(buf.as_mut_slice());
+        encoder
+            .encode_with_hotspot(pixels, "image", 8, 7, None)
+            .unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/basic.xbm")
+        );
+    }
+}
+
+#[test]
+fn encode_with_leading_comment() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+    let comment = "/*\n * The letter \"B\".\n */\n\n// Converted from basic.xbm.";
+
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).leading_comment(comment);
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/leading_comment.xbm")
+        );
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).leading_comment(comment);
+        let mut encoder = encoder.begin("image", 8, 7, None, None).unwrap();
+        for row in pixels.chunks(8) {
+            encoder.write_row(row).unwrap();
+        }
+        encoder.finish().unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/leading_comment.xbm")
+        );
+    }
+    {
+        let encoder = Encoder::new(Vec::new()).leading_comment(comment);
+        let len = encoder.estimate_len("image", 8, 7, None, None).unwrap();
+        assert_eq!(len, include_str!("data/leading_comment.xbm").len());
+    }
+}
+
+#[test]
+fn encode_with_array_comment() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).array_comment("8x7 icon");
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/array_comment.xbm")
+        );
+
+        let decoder = Decoder::new(Cursor::new(buf)).unwrap();
+        assert_eq!(decoder.decode_to_vec().unwrap(), pixels);
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).array_comment("8x7 icon");
+        let mut encoder = encoder.begin("image", 8, 7, None, None).unwrap();
+        for row in pixels.chunks(8) {
+            encoder.write_row(row).unwrap();
+        }
+        encoder.finish().unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/array_comment.xbm")
+        );
+    }
+    {
+        let encoder = Encoder::new(Vec::new()).array_comment("8x7 icon");
+        let len = encoder.estimate_len("image", 8, 7, None, None).unwrap();
+        assert_eq!(len, include_str!("data/array_comment.xbm").len());
+    }
+    for comment in ["8x7 */ icon", "8x7\nicon"] {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).array_comment(comment);
+        let err = encoder
+            .encode(pixels, "image", 8, 7, None, None)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            err.to_string(),
+            "the array comment contains either `*/` or a newline"
+        );
+        assert!(buf.is_empty());
+    }
+}
+
+#[test]
+fn encode_with_indent() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).indent("\t");
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/tab_indent.xbm")
+        );
+
+        let decoder = Decoder::new(Cursor::new(buf)).unwrap();
+        assert_eq!(decoder.decode_to_vec().unwrap(), pixels);
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).indent("\t");
+        let mut encoder = encoder.begin("image", 8, 7, None, None).unwrap();
+        for row in pixels.chunks(8) {
+            encoder.write_row(row).unwrap();
+        }
+        encoder.finish().unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/tab_indent.xbm")
+        );
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).indent("");
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert!(str::from_utf8(&buf)
+            .unwrap()
+            .contains("{\n0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,\n};"));
+    }
+    {
+        let encoder = Encoder::new(Vec::new()).indent("\t");
+        let len = encoder.estimate_len("image", 8, 7, None, None).unwrap();
+        assert_eq!(len, include_str!("data/tab_indent.xbm").len());
+    }
+    for indent in ["  x", " \n"] {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).indent(indent);
+        let err = encoder
+            .encode(pixels, "image", 8, 7, None, None)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            err.to_string(),
+            "the indentation contains characters other than spaces and tabs"
+        );
+        assert!(buf.is_empty());
+    }
+}
+
+#[test]
+fn encode_with_crc32() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = [u8::default(); 163];
+    let encoder = Encoder::new(buf.as_mut_slice()).emit_crc32(true);
+    encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        include_str!("data/crc32.xbm")
+    );
+}
+
+#[test]
+fn encode_with_element_type() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let mut buf = Vec::with_capacity(132);
+        let encoder = Encoder::new(buf.by_ref()).element_type(ElementType::U8);
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/basic.xbm")
+        );
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).element_type(ElementType::U16);
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            indoc! {"
+                #define image_width 8
+                #define image_height 7
+                static unsigned short image_bits[] = {
+                    0x0000, 0x001C, 0x0024, 0x001C, 0x0024, 0x001C, 0x0000,
+                };
+            "}
+        );
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).element_type(ElementType::U32);
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            indoc! {"
+                #define image_width 8
+                #define image_height 7
+                static unsigned int image_bits[] = {
+                    0x00000000, 0x0000001C, 0x00000024, 0x0000001C, 0x00000024, 0x0000001C,
+                    0x00000000,
+                };
+            "}
+        );
+    }
+}
+
+#[test]
+fn encode_with_bit_order() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = Vec::new();
+    let encoder = Encoder::new(buf.by_ref())
+        .emit_crc32(true)
+        .bit_order(BitOrder::Msb);
+    encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        indoc! {"
+            #define image_width 8
+            #define image_height 7
+            #define image_crc32 0x5C4051F3
+            static unsigned char image_bits[] = {
+                0x00, 0x38, 0x24, 0x38, 0x24, 0x38, 0x00,
+            };
+        "}
+    );
+    let decoder = Decoder::new(Cursor::new(&buf))
+        .unwrap()
+        .verify_crc32(true)
+        .bit_order(BitOrder::Msb);
+    assert_eq!(decoder.decode_to_vec().unwrap(), pixels);
+
+    let mut buf = Vec::new();
+    let encoder = Encoder::new(buf.by_ref())
+        .element_type(ElementType::U16)
+        .bit_order(BitOrder::Msb);
+    encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    assert!(str::from_utf8(&buf)
+        .unwrap()
+        .contains("0x0000, 0x0038, 0x0024, 0x0038, 0x0024, 0x0038, 0x0000,"));
+
+    let mut buf = Vec::new();
+    let encoder = Encoder::new(buf.by_ref()).bit_order(BitOrder::Msb);
+    let mut rows = encoder.begin("image", 8, 7, None, None).unwrap();
+    for row in pixels.chunks(8) {
+        rows.write_row(row).unwrap();
+    }
+    rows.finish().unwrap();
+    assert!(str::from_utf8(&buf)
+        .unwrap()
+        .contains("0x00, 0x38, 0x24, 0x38, 0x24, 0x38, 0x00,"));
+
+    let mut buf = Vec::new();
+    let encoder = Encoder::new(buf.by_ref()).bit_order(BitOrder::Lsb);
+    encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    assert_eq!(buf, include_bytes!("data/basic.xbm"));
+}
+
+#[test]
+fn encode_black_image_with_element_type() {
+    let pixels = [1; 16 * 2];
+
+    for (element_type, expected) in [
+        (
+            ElementType::U8,
+            indoc! {"
+                #define image_width 16
+                #define image_height 2
+                static unsigned char image_bits[] = {
+                    0xFF, 0xFF, 0xFF, 0xFF,
+                };
+            "},
+        ),
+        (
+            ElementType::U16,
+            indoc! {"
+                #define image_width 16
+                #define image_height 2
+                static unsigned short image_bits[] = {
+                    0xFFFF, 0xFFFF,
+                };
+            "},
+        ),
+        (
+            ElementType::U32,
+            indoc! {"
+                #define image_width 16
+                #define image_height 2
+                static unsigned int image_bits[] = {
+                    0x0000FFFF, 0x0000FFFF,
+                };
+            "},
+        ),
+    ] {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).element_type(element_type);
+        encoder.encode(pixels, "image", 16, 2, None, None).unwrap();
+        assert_eq!(str::from_utf8(&buf).unwrap(), expected, "{element_type:?}");
+    }
+}
+
+#[test]
+fn encode_16x14_with_element_type() {
+    // "B" (16x14)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = Vec::new();
+    let encoder = Encoder::new(buf.by_ref()).element_type(ElementType::U16);
+    encoder.encode(pixels, "image", 16, 14, None, None).unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        indoc! {"
+            #define image_width 16
+            #define image_height 14
+            static unsigned short image_bits[] = {
+                0x0000, 0x0000, 0x03F0, 0x03F0, 0x0C30, 0x0C30, 0x03F0, 0x03F0,
+                0x0C30, 0x0C30, 0x03F0, 0x03F0, 0x0000, 0x0000,
+            };
+        "}
+    );
+}
+
+#[test]
+fn encode_with_trailing_newline() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let mut buf = Vec::with_capacity(132);
+        let encoder = Encoder::new(buf.by_ref()).trailing_newline(true);
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/basic.xbm")
+        );
+    }
+    {
+        let mut buf = Vec::with_capacity(131);
+        let encoder = Encoder::new(buf.by_ref()).trailing_newline(false);
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/basic.xbm").trim_end_matches('\n')
+        );
+    }
+}
+
+#[test]
+fn encode_with_visual_grid() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = Vec::new();
+    let encoder = Encoder::new(buf.by_ref()).emit_visual_grid(true);
+    encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        indoc! {"
+            #define image_width 8
+            #define image_height 7
+            static unsigned char image_bits[] = {
+                0x00, /* ........ */
+                0x1C, /* ..###... */
+                0x24, /* ..#..#.. */
+                0x1C, /* ..###... */
+                0x24, /* ..#..#.. */
+                0x1C, /* ..###... */
+                0x00, /* ........ */
+            };
+        "}
+    );
+}
+
+#[test]
+fn encode_with_wrap_per_row() {
+    // "I" (14x12)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+    let expected = indoc! {"
+        #define image_width 14
+        #define image_height 12
+        static unsigned char image_bits[] = {
+            0x00, 0x00,
+            0x00, 0x00,
+            0xF0, 0x03,
+            0xF0, 0x03,
+            0xC0, 0x00,
+            0xC0, 0x00,
+            0xC0, 0x00,
+            0xC0, 0x00,
+            0xF0, 0x03,
+            0xF0, 0x03,
+            0x00, 0x00,
+            0x00, 0x00,
+        };
+    "};
+
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).wrap_per_row(true);
+        encoder.encode(pixels, "image", 14, 12, None, None).unwrap();
+        assert_eq!(str::from_utf8(&buf).unwrap(), expected);
+
+        let decoder = Decoder::new(Cursor::new(buf)).unwrap();
+        assert_eq!(decoder.decode_to_vec().unwrap(), pixels);
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).wrap_per_row(true);
+        let mut encoder = encoder.begin("image", 14, 12, None, None).unwrap();
+        for row in pixels.chunks(14) {
+            encoder.write_row(row).unwrap();
+        }
+        encoder.finish().unwrap();
+        assert_eq!(str::from_utf8(&buf).unwrap(), expected);
+    }
+    {
+        let encoder = Encoder::new(Vec::new()).wrap_per_row(true);
+        let len = encoder.estimate_len("image", 14, 12, None, None).unwrap();
+        assert_eq!(len, expected.len());
+    }
+}
+
+#[test]
+fn encode_width_14_with_visual_grid() {
+    // "I" (14x12)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = Vec::new();
+    let encoder = Encoder::new(buf.by_ref())
+        .element_type(ElementType::U16)
+        .emit_visual_grid(true);
+    encoder.encode(pixels, "image", 14, 12, None, None).unwrap();
     assert_eq!(
         str::from_utf8(&buf).unwrap(),
-        include_str!("data/16x14.xbm")
+        indoc! {"
+            #define image_width 14
+            #define image_height 12
+            static unsigned short image_bits[] = {
+                0x0000, /* .............. */
+                0x0000, /* .............. */
+                0x03F0, /* ....######.... */
+                0x03F0, /* ....######.... */
+                0x00C0, /* ......##...... */
+                0x00C0, /* ......##...... */
+                0x00C0, /* ......##...... */
+                0x00C0, /* ......##...... */
+                0x03F0, /* ....######.... */
+                0x03F0, /* ....######.... */
+                0x0000, /* .............. */
+                0x0000, /* .............. */
+            };
+        "}
     );
 }
 
 #[test]
-fn encode_width_7() {
-    // "I" (7x6)
-    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\
-                   \x00\x00\x01\x01\x01\x00\x00\
-                   \x00\x00\x00\x01\x00\x00\x00\
-                   \x00\x00\x00\x01\x00\x00\x00\
-                   \x00\x00\x01\x01\x01\x00\x00\
-                   \x00\x00\x00\x00\x00\x00\x00";
+fn encode_and_decode_x10() {
+    use xbm::decode::Format;
 
-    let mut buf = [u8::default(); 126];
-    let encoder = Encoder::new(buf.as_mut_slice());
-    encoder.encode(pixels, "image", 7, 6, None, None).unwrap();
-    assert_eq!(
-        str::from_utf8(&buf).unwrap(),
-        include_str!("data/width_7.xbm")
-    );
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+        "tests/data/qr_code.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let decoder = Decoder::new(reader).unwrap();
+        let (width, height) = (decoder.width(), decoder.height());
+        let pixels = decoder.decode_to_vec().unwrap();
+
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref())
+            .element_type(ElementType::U16)
+            .emit_crc32(true)
+            .emit_visual_grid(true);
+        encoder
+            .encode(&pixels, "image", width, height, None, None)
+            .unwrap();
+
+        let decoder = Decoder::new(Cursor::new(buf))
+            .unwrap()
+            .verify_crc32(true)
+            .verify_row_length(true);
+        assert_eq!(decoder.format(), Format::X10);
+        assert_eq!(decoder.decode_to_vec().unwrap(), pixels);
+    }
 }
 
 #[test]
-fn encode_width_14() {
+fn encode_with_pad_width_to_byte() {
     // "I" (14x12)
     let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
                    \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
@@ -108,17 +1031,47 @@ fn encode_width_14() {
                    \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
                    \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
 
-    let mut buf = [u8::default(); 240];
-    let encoder = Encoder::new(buf.as_mut_slice());
-    encoder.encode(pixels, "image", 14, 12, None, None).unwrap();
-    assert_eq!(
-        str::from_utf8(&buf).unwrap(),
-        include_str!("data/width_14.xbm")
-    );
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).pad_width_to_byte(true);
+        encoder.encode(pixels, "image", 14, 12, None, None).unwrap();
+        let expected =
+            include_str!("data/width_14.xbm").replace("image_width 14", "image_width 16");
+        assert_eq!(str::from_utf8(&buf).unwrap(), expected);
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref())
+            .pad_width_to_byte(true)
+            .emit_visual_grid(true);
+        let mut encoder = encoder.begin("image", 14, 12, None, None).unwrap();
+        for row in pixels.chunks(14).skip(2).take(1) {
+            encoder.write_row(row).unwrap();
+        }
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            indoc! {"
+                #define image_width 16
+                #define image_height 12
+                static unsigned char image_bits[] = {
+                    0xF0, 0x03, /* ....######...... */
+            "}
+        );
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).pad_width_to_byte(true);
+        encoder
+            .encode(&pixels[..112], "image", 8, 14, None, None)
+            .unwrap();
+        assert!(str::from_utf8(&buf)
+            .unwrap()
+            .starts_with("#define image_width 8\n"));
+    }
 }
 
 #[test]
-fn encode_with_hotspot() {
+fn encode_with_portable_types() {
     // "B" (8x7)
     let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
                    \x00\x00\x01\x01\x01\x00\x00\x00\
@@ -128,15 +1081,144 @@ fn encode_with_hotspot() {
                    \x00\x00\x01\x01\x01\x00\x00\x00\
                    \x00\x00\x00\x00\x00\x00\x00\x00";
 
-    let mut buf = [u8::default(); 176];
-    let encoder = Encoder::new(buf.as_mut_slice());
-    encoder
-        .encode(pixels, "image", 8, 7, Some(4), Some(3))
-        .unwrap();
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).portable_types(true);
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            indoc! {"
+                #define image_width 8
+                #define image_height 7
+                #if defined(__STDC_VERSION__) && __STDC_VERSION__ >= 199901L
+                #include <stdint.h>
+                #elif !defined(UINT8_MAX)
+                typedef unsigned char uint8_t;
+                #endif
+                static uint8_t image_bits[] = {
+                    0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+                };
+            "}
+        );
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref())
+            .element_type(ElementType::U32)
+            .portable_types(true);
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            indoc! {"
+                #define image_width 8
+                #define image_height 7
+                #if defined(__STDC_VERSION__) && __STDC_VERSION__ >= 199901L
+                #include <stdint.h>
+                #elif !defined(UINT32_MAX)
+                typedef unsigned long uint32_t;
+                #endif
+                static uint32_t image_bits[] = {
+                    0x00000000, 0x0000001C, 0x00000024, 0x0000001C, 0x00000024, 0x0000001C,
+                    0x00000000,
+                };
+            "}
+        );
+    }
+    for element_type in [ElementType::U8, ElementType::U16] {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref())
+            .element_type(element_type)
+            .emit_crc32(true)
+            .portable_types(true);
+        encoder
+            .encode(pixels, "image", 8, 7, Some(4), Some(3))
+            .unwrap();
+        let decoder = Decoder::new(Cursor::new(&buf)).unwrap().verify_crc32(true);
+        assert_eq!(decoder.x_hot(), Some(4));
+        assert_eq!(decoder.decode_to_vec().unwrap(), pixels);
+    }
+    {
+        let mut buf = Vec::with_capacity(132);
+        let encoder = Encoder::new(buf.by_ref()).portable_types(false);
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/basic.xbm")
+        );
+    }
+}
+
+#[test]
+fn encode_with_brace_on_next_line() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = Vec::new();
+    let encoder = Encoder::new(&mut buf).brace_on_next_line(true);
+    encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
     assert_eq!(
         str::from_utf8(&buf).unwrap(),
-        include_str!("data/hotspot.xbm")
+        include_str!("data/brace_on_next_line.xbm")
     );
+
+    let decoder = Decoder::new(Cursor::new(buf)).unwrap();
+    assert_eq!(decoder.decode_to_vec().unwrap(), pixels);
+}
+
+#[test]
+fn encode_with_index_comments() {
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).index_comments(true);
+        encoder.encode(pixels, "image", 8, 7, None, None).unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            indoc! {"
+                #define image_width 8
+                #define image_height 7
+                static unsigned char image_bits[] = {
+                    0x00, /* [0] */ 0x1C, /* [1] */ 0x24, /* [2] */ 0x1C, /* [3] */ 0x24, /* [4] */ 0x1C, /* [5] */ 0x00, /* [6] */
+                };
+            "}
+        );
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref())
+            .index_comments(true)
+            .emit_visual_grid(true);
+        let mut encoder = encoder.begin("image", 8, 7, None, None).unwrap();
+        for row in pixels.chunks(8).take(3) {
+            encoder.write_row(row).unwrap();
+        }
+        assert_eq!(encoder.rows(), 3);
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            indoc! {"
+                #define image_width 8
+                #define image_height 7
+                static unsigned char image_bits[] = {
+                    0x00, /* [0] */ /* ........ */
+                    0x1C, /* [1] */ /* ..###... */
+                    0x24, /* [2] */ /* ..#..#.. */
+            "}
+        );
+    }
 }
 
 #[test]
@@ -328,6 +1410,266 @@ fn encode_with_invalid_dimensions() {
     let _ = encoder.encode(pixels, "image", 4, 3, None, None);
 }
 
+#[test]
+fn encode_iter() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+    ] {
+        let decoder = Decoder::new(File::open(path).map(BufReader::new).unwrap()).unwrap();
+        let (width, height) = (decoder.width(), decoder.height());
+        let pixels = decoder.decode_to_vec().unwrap();
+
+        for element_type in [ElementType::U8, ElementType::U16] {
+            let mut expected = Vec::new();
+            let encoder = Encoder::new(expected.by_ref())
+                .element_type(element_type)
+                .emit_crc32(true)
+                .emit_visual_grid(true);
+            encoder
+                .encode(&pixels, "image", width, height, None, None)
+                .unwrap();
+
+            let mut buf = Vec::new();
+            let encoder = Encoder::new(buf.by_ref())
+                .element_type(element_type)
+                .emit_crc32(true)
+                .emit_visual_grid(true);
+            encoder
+                .encode_iter(
+                    pixels.iter().map(|&p| p == 1),
+                    "image",
+                    width,
+                    height,
+                    None,
+                    None,
+                )
+                .unwrap();
+            assert_eq!(buf, expected);
+        }
+    }
+}
+
+#[test]
+fn encode_iter_with_invalid_length() {
+    for len in [55, 57] {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref());
+        let err = encoder
+            .encode_iter(iter::repeat(false).take(len), "image", 8, 7, None, None)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            err.to_string(),
+            "the number of the pixels and the image dimensions are different"
+        );
+        assert!(buf.is_empty());
+    }
+
+    let mut buf = Vec::new();
+    let encoder = Encoder::new(buf.by_ref());
+    let err = encoder
+        .encode_iter(iter::repeat(false), "image", 8, 7, Some(4), None)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "only one of `x_hot` and `y_hot` is `Some`");
+}
+
+#[test]
+fn encode_row_by_row() {
+    // "I" (14x12)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x01\x01\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x01\x01\x01\x01\x01\x01\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = Vec::with_capacity(240);
+    let encoder = Encoder::new(buf.by_ref());
+    let mut encoder = encoder.begin("image", 14, 12, None, None).unwrap();
+    for (i, row) in pixels.chunks(14).enumerate() {
+        assert_eq!(encoder.rows(), i);
+        encoder.write_row(row).unwrap();
+    }
+    encoder.finish().unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        include_str!("data/width_14.xbm")
+    );
+
+    for element_type in [ElementType::U8, ElementType::U16, ElementType::U32] {
+        for visual_grid in [false, true] {
+            let mut expected = Vec::new();
+            let encoder = Encoder::new(expected.by_ref())
+                .element_type(element_type)
+                .emit_visual_grid(visual_grid);
+            encoder
+                .encode(pixels, "image", 14, 12, Some(7), Some(6))
+                .unwrap();
+
+            let mut buf = Vec::new();
+            let encoder = Encoder::new(buf.by_ref())
+                .element_type(element_type)
+                .emit_visual_grid(visual_grid);
+            let mut encoder = encoder.begin("image", 14, 12, Some(7), Some(6)).unwrap();
+            for row in pixels.chunks(14) {
+                encoder.write_row(row).unwrap();
+            }
+            encoder.finish().unwrap();
+            assert_eq!(buf, expected);
+        }
+    }
+}
+
+#[test]
+fn encode_row_by_row_with_invalid_rows() {
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref());
+        let mut encoder = encoder.begin("image", 8, 1, None, None).unwrap();
+        let err = encoder.write_row([u8::default(); 7]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "`row` and the width are different");
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref());
+        let mut encoder = encoder.begin("image", 8, 1, None, None).unwrap();
+        let err = encoder.write_row([2; 8]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "`row` contains values other than `0` and `1`"
+        );
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref());
+        let mut encoder = encoder.begin("image", 8, 1, None, None).unwrap();
+        encoder.write_row([u8::default(); 8]).unwrap();
+        let err = encoder.write_row([u8::default(); 8]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "all rows have already been written");
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref());
+        let mut encoder = encoder.begin("image", 8, 2, None, None).unwrap();
+        encoder.write_row([u8::default(); 8]).unwrap();
+        let err = encoder.finish().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            err.to_string(),
+            "the number of the written rows and the height are different"
+        );
+    }
+}
+
+#[test]
+fn encode_with_zero_dimension() {
+    for (width, height) in [(0, 0), (0, 7), (8, 0)] {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref());
+        let err = encoder
+            .encode([], "image", width, height, None, None)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "either `width` or `height` is `0`");
+        assert!(buf.is_empty());
+    }
+}
+
+#[test]
+fn estimate_len() {
+    type Options = fn(Encoder<&mut Vec<u8>>) -> Encoder<&mut Vec<u8>>;
+
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+        "tests/data/qr_code.xbm",
+    ] {
+        let decoder = Decoder::new(File::open(path).map(BufReader::new).unwrap()).unwrap();
+        let (width, height) = (decoder.width(), decoder.height());
+        let pixels = decoder.decode_to_vec().unwrap();
+
+        let options: [Options; 7] = [
+            |e| e,
+            |e| e.emit_crc32(true).trailing_newline(false),
+            |e| e.element_type(ElementType::U16),
+            |e| e.element_type(ElementType::U32).emit_visual_grid(true),
+            |e| e.portable_types(true).pad_width_to_byte(true),
+            |e| e.index_comments(true),
+            |e| {
+                e.emit_visual_grid(true)
+                    .pad_width_to_byte(true)
+                    .index_comments(true)
+            },
+        ];
+        for option in options {
+            for (x_hot, y_hot) in [(None, None), (Some(4), Some(3))] {
+                let mut buf = Vec::new();
+                let encoder = option(Encoder::new(buf.by_ref()));
+                let len = encoder
+                    .estimate_len("image", width, height, x_hot, y_hot)
+                    .unwrap();
+                encoder
+                    .encode(&pixels, "image", width, height, x_hot, y_hot)
+                    .unwrap();
+                assert_eq!(len, buf.len());
+            }
+        }
+    }
+
+    let encoder = Encoder::new(Vec::new()).index_comments(true);
+    let len = encoder.estimate_len("image", u32::MAX, u32::MAX, None, None);
+    assert!(len.is_none());
+}
+
+#[test]
+fn begin_with_invalid_header() {
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref());
+        let err = encoder.begin("0image", 8, 7, None, None).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "invalid C identifier prefix");
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref());
+        let err = encoder.begin("image", 8, 7, Some(4), None).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "only one of `x_hot` and `y_hot` is `Some`");
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref());
+        let err = encoder.begin("image", 0, 7, None, None).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "either `width` or `height` is `0`");
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).emit_crc32(true);
+        let err = encoder.begin("image", 8, 7, None, None).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            err.to_string(),
+            "the CRC-32 checksum cannot be written row by row"
+        );
+    }
+}
+
 #[cfg(feature = "image")]
 #[test]
 fn image_encoder_from_l1() {
@@ -378,6 +1720,115 @@ fn image_encoder_from_l8() {
     );
 }
 
+#[cfg(feature = "image")]
+#[test]
+fn image_encoder_from_la8() {
+    use image::{ExtendedColorType, ImageEncoder};
+
+    // "B" (8x7) with transparent black pixels around it
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\xFF\x00\xFF\x00\xFF\xFF\xFF\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\xFF\xFF\xFF\xFF\xFF\x00\xFF\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\xFF\x00\xFF\x00\xFF\xFF\xFF\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\xFF\xFF\xFF\xFF\xFF\x00\xFF\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\xFF\x00\xFF\x00\xFF\xFF\xFF\x00\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+
+    let mut buf = [u8::default(); 132];
+    let encoder = Encoder::new(buf.as_mut_slice());
+    encoder
+        .write_image(pixels, 8, 7, ExtendedColorType::La8)
+        .unwrap();
+    assert_eq!(
+        str::from_utf8(&buf).unwrap(),
+        include_str!("data/basic.xbm")
+    );
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn image_encoder_with_width_not_multiple_of_8() {
+    use image::{ExtendedColorType, ImageEncoder};
+
+    // "I" (7x6)
+    let pixels = b"\xFF\xFF\xFF\xFF\xFF\xFF\xFF\
+                   \xFF\xFF\x00\x00\x00\xFF\xFF\
+                   \xFF\xFF\xFF\x00\xFF\xFF\xFF\
+                   \xFF\xFF\xFF\x00\xFF\xFF\xFF\
+                   \xFF\xFF\x00\x00\x00\xFF\xFF\
+                   \xFF\xFF\xFF\xFF\xFF\xFF\xFF";
+
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref());
+        encoder
+            .write_image(pixels, 7, 6, ExtendedColorType::L8)
+            .unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/width_7.xbm")
+        );
+
+        let decoder = Decoder::new(Cursor::new(buf)).unwrap();
+        assert_eq!(decoder.width(), 7);
+        let image = decoder.into_gray_image().unwrap();
+        assert_eq!(image.into_raw(), pixels);
+    }
+    {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(buf.by_ref()).pad_width_to_byte(true);
+        encoder
+            .write_image(pixels, 7, 6, ExtendedColorType::L8)
+            .unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/width_7_padded.xbm")
+        );
+
+        let decoder = Decoder::new(Cursor::new(buf)).unwrap();
+        assert_eq!(decoder.width(), 8);
+        let image = decoder.into_gray_image().unwrap();
+        for (row, expected) in image.into_raw().chunks(8).zip(pixels.chunks(7)) {
+            assert_eq!(&row[..7], expected);
+            assert_eq!(row[7], u8::MAX);
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn image_encoder_with_invalid_dimensions() {
+    use image::{error::ParameterErrorKind, ExtendedColorType, ImageEncoder, ImageError};
+
+    // "B" (8x7)
+    let pixels = b"\x00\x00\x00\x00\x00\x00\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x01\x00\x00\x01\x00\x00\
+                   \x00\x00\x01\x01\x01\x00\x00\x00\
+                   \x00\x00\x00\x00\x00\x00\x00\x00";
+
+    for color_type in [
+        ExtendedColorType::L1,
+        ExtendedColorType::L8,
+        ExtendedColorType::La8,
+    ] {
+        for (width, height) in [(4, 3), (u32::MAX, u32::MAX)] {
+            let mut buf = [];
+            let encoder = Encoder::new(buf.as_mut_slice());
+            let err = encoder
+                .write_image(pixels, width, height, color_type)
+                .unwrap_err();
+            if let ImageError::Parameter(err) = err {
+                assert_eq!(err.kind(), ParameterErrorKind::DimensionMismatch);
+            } else {
+                unreachable!();
+            }
+        }
+    }
+}
+
 #[cfg(feature = "image")]
 #[test]
 fn image_encoder_from_unsupported_color_type() {
@@ -392,6 +1843,45 @@ fn image_encoder_from_unsupported_color_type() {
     assert!(result.is_err());
 }
 
+#[cfg(feature = "image")]
+#[test]
+fn encode_gray_image() {
+    use image::GrayImage;
+
+    // "B" (8x7)
+    let pixels = b"\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\
+                   \xFF\xFF\x00\x00\x00\xFF\xFF\xFF\
+                   \xFF\xFF\x00\xFF\xFF\x00\xFF\xFF\
+                   \xFF\xFF\x00\x00\x00\xFF\xFF\xFF\
+                   \xFF\xFF\x00\xFF\xFF\x00\xFF\xFF\
+                   \xFF\xFF\x00\x00\x00\xFF\xFF\xFF\
+                   \xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF";
+    let image = GrayImage::from_raw(8, 7, pixels.to_vec()).unwrap();
+
+    {
+        let mut buf = [u8::default(); 132];
+        let encoder = Encoder::new(buf.as_mut_slice());
+        encoder
+            .encode_gray_image(&image, "image", None, None)
+            .unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/basic.xbm")
+        );
+    }
+    {
+        let mut buf = [u8::default(); 176];
+        let encoder = Encoder::new(buf.as_mut_slice());
+        encoder
+            .encode_gray_image(&image, "image", Some(4), Some(3))
+            .unwrap();
+        assert_eq!(
+            str::from_utf8(&buf).unwrap(),
+            include_str!("data/hotspot.xbm")
+        );
+    }
+}
+
 #[cfg(feature = "image")]
 #[test]
 fn png_to_xbm() {
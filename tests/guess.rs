@@ -0,0 +1,35 @@
+// SPDX-FileCopyrightText: 2024 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::fs;
+
+#[test]
+fn guess_is_xbm() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/hotspot.xbm",
+        "tests/data/leading_comment.xbm",
+        "tests/data/x10.xbm",
+    ] {
+        assert!(xbm::guess_is_xbm(&fs::read(path).unwrap()), "{path}");
+    }
+    assert!(xbm::guess_is_xbm(b"\xEF\xBB\xBF#define image_width 8"));
+    assert!(xbm::guess_is_xbm(b"  # define image_width 8"));
+    assert!(xbm::guess_is_xbm(
+        b"// icon\n/* A\n * B */\n#define image_width 8"
+    ));
+}
+
+#[test]
+fn guess_is_xbm_with_other_data() {
+    assert!(!xbm::guess_is_xbm(b""));
+    assert!(!xbm::guess_is_xbm(b"P1\n8 7\n"));
+    assert!(!xbm::guess_is_xbm(b"\x89PNG\r\n\x1A\n"));
+    assert!(!xbm::guess_is_xbm(b"/* XPM */\nstatic char *image[] = {"));
+    assert!(!xbm::guess_is_xbm(b"/* #define image_width 8"));
+    assert!(!xbm::guess_is_xbm(b"#define image_height 7"));
+    assert!(!xbm::guess_is_xbm(b"#define _width 8"));
+    assert!(!xbm::guess_is_xbm(b"#defineimage_width 8"));
+    assert!(!xbm::guess_is_xbm(b"#include <stdio.h>"));
+}
@@ -0,0 +1,179 @@
+// SPDX-FileCopyrightText: 2024 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{
+    fs::File,
+    io::{BufReader, Cursor},
+};
+
+use indoc::indoc;
+
+#[test]
+fn lint() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/hotspot.xbm",
+        "tests/data/name.xbm",
+        "tests/data/qr_code.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        assert!(xbm::lint(reader).unwrap().is_empty(), "{path}");
+    }
+}
+
+#[test]
+fn lint_minified() {
+    let reader = File::open("tests/data/basic_minified.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    assert!(xbm::lint(reader).unwrap().is_empty());
+
+    let reader = File::open("tests/data/basic_single_line.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    assert!(xbm::lint(reader).unwrap().is_empty());
+}
+
+#[test]
+fn lint_with_spaced_define() {
+    let reader = File::open("tests/data/spaced_define.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    assert!(xbm::lint(reader).unwrap().is_empty());
+}
+
+#[test]
+fn lint_with_brace_on_next_line() {
+    let reader = File::open("tests/data/brace_on_next_line.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    assert!(xbm::lint(reader).unwrap().is_empty());
+
+    let image = indoc! {"
+        #define image_width 8
+        #define image_height 7
+        static unsigned char image_bits[] =
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+        };
+    "};
+    let lints = xbm::lint(Cursor::new(image)).unwrap();
+    assert_eq!(lints[0].line(), 4);
+    assert_eq!(
+        lints[0].message(),
+        "missing `{` after the array declaration"
+    );
+}
+
+#[test]
+fn lint_with_signed_char() {
+    let reader = File::open("tests/data/without_unsigned.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let lints = xbm::lint(reader).unwrap();
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].line(), 3);
+    assert_eq!(lints[0].message(), "array is declared as signed `char`");
+    assert_eq!(
+        lints[0].to_string(),
+        "line 3: array is declared as signed `char`"
+    );
+}
+
+#[test]
+fn lint_with_upper_prefix() {
+    let reader = File::open("tests/data/basic_upper_prefix.xbm")
+        .map(BufReader::new)
+        .unwrap();
+    let lints = xbm::lint(reader).unwrap();
+    assert_eq!(lints.len(), 7);
+    assert!(lints
+        .iter()
+        .all(|l| l.line() == 4 && l.message().contains("uppercase prefix `0X`")));
+}
+
+#[test]
+fn lint_with_multiple_deviations() {
+    let image = indoc! {"
+        /* B */
+        #define image_width 0x8
+        #define image_height 7
+        #define image_x_hot 4
+        static char test_bits[] = {
+            0x00, 0x1C, 0x24, 0x1c, 0x24, 0x1C, 0x0,
+        };
+    "};
+
+    let lints = xbm::lint(Cursor::new(image))
+        .unwrap()
+        .into_iter()
+        .map(|l| (l.line(), l.message().to_owned()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lints,
+        [
+            (1, String::from("comments are not part of the format")),
+            (2, String::from("value `0x8` is not a decimal integer")),
+            (5, String::from("array is declared as signed `char`")),
+            (5, String::from("inconsistent identifier `test_bits[]`")),
+            (6, String::from("hex byte `0x0` does not have two digits")),
+            (
+                7,
+                String::from("only one of the hotspot coordinates is defined")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn lint_with_invalid_image_size() {
+    let image = indoc! {"
+        #define image_width 7
+        #define image_height 7
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00, 0x00,
+        };
+    "};
+
+    let lints = xbm::lint(Cursor::new(image)).unwrap();
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].line(), 5);
+    assert_eq!(
+        lints[0].message(),
+        "array has `8` bytes (expected `7` bytes)"
+    );
+}
+
+#[test]
+fn lint_with_missing_parts() {
+    let image = indoc! {"
+        #define image_height 7
+        #define image_depth 1
+        static unsigned char image_bits[] = {
+            0x00, 0x1C, 0x24, 0x1C, 0x24, 0x1C, 0x00,
+    "};
+
+    let lints = xbm::lint(Cursor::new(image))
+        .unwrap()
+        .into_iter()
+        .map(|l| (l.line(), l.message().to_owned()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lints,
+        [
+            (1, String::from("height is defined before the width")),
+            (2, String::from("unknown identifier `image_depth`")),
+            (4, String::from("missing width")),
+            (4, String::from("array is not terminated by `};`")),
+        ]
+    );
+}
+
+#[test]
+fn lint_from_blank_input() {
+    let lints = xbm::lint(Cursor::new("")).unwrap();
+    assert_eq!(lints.len(), 3);
+}
@@ -0,0 +1,145 @@
+// SPDX-FileCopyrightText: 2024 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use proptest::{collection, option, prelude::*};
+use xbm::{encode::ElementType, Decoder, Encoder};
+
+/// A binary image with the header.
+#[derive(Debug)]
+struct Bitmap {
+    width: u32,
+    height: u32,
+    hotspot: Option<(u32, u32)>,
+    pixels: Vec<u8>,
+}
+
+fn bitmap() -> impl Strategy<Value = Bitmap> {
+    (1_u32..=40, 1_u32..=40).prop_flat_map(|(width, height)| {
+        let len = usize::try_from(width * height).unwrap();
+        (
+            option::of((0..width, 0..height)),
+            collection::vec(0_u8..=1, len),
+        )
+            .prop_map(move |(hotspot, pixels)| Bitmap {
+                width,
+                height,
+                hotspot,
+                pixels,
+            })
+    })
+}
+
+fn element_type() -> impl Strategy<Value = ElementType> {
+    prop_oneof![
+        Just(ElementType::U8),
+        Just(ElementType::U16),
+        Just(ElementType::U32),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn encode_and_decode(bitmap in bitmap(), crc32: bool, visual_grid: bool) {
+        let (x_hot, y_hot) = bitmap.hotspot.unzip();
+
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(&mut buf)
+            .emit_crc32(crc32)
+            .emit_visual_grid(visual_grid);
+        encoder
+            .encode(&bitmap.pixels, "image", bitmap.width, bitmap.height, x_hot, y_hot)
+            .unwrap();
+
+        let decoder = Decoder::from_bytes(&buf)
+            .unwrap()
+            .verify_crc32(true)
+            .verify_row_length(visual_grid);
+        prop_assert_eq!(decoder.width(), bitmap.width);
+        prop_assert_eq!(decoder.height(), bitmap.height);
+        prop_assert_eq!(decoder.x_hot(), x_hot);
+        prop_assert_eq!(decoder.y_hot(), y_hot);
+        prop_assert_eq!(decoder.crc32().is_some(), crc32);
+        prop_assert_eq!(decoder.decode_to_vec().unwrap(), bitmap.pixels);
+    }
+
+    #[test]
+    fn encode_with_element_type_and_decode_packed(
+        bitmap in bitmap(),
+        element_type in element_type(),
+    ) {
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(&mut buf);
+        encoder
+            .encode(&bitmap.pixels, "image", bitmap.width, bitmap.height, None, None)
+            .unwrap();
+        let expected = Decoder::from_bytes(&buf)
+            .unwrap()
+            .decode_packed()
+            .unwrap();
+
+        // Each row is padded to the element size, so the packed bytes of each
+        // row are the bytes for the width followed by the zero padding.
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(&mut buf).element_type(element_type);
+        encoder
+            .encode(&bitmap.pixels, "image", bitmap.width, bitmap.height, None, None)
+            .unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        let body = &text[text.find('{').unwrap() + 1..text.rfind("};").unwrap()];
+        let size = match element_type {
+            ElementType::U8 => 1,
+            ElementType::U16 => 2,
+            ElementType::U32 => 4,
+        };
+        let actual = body
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .flat_map(|t| {
+                let value = u32::from_str_radix(&t[2..], 16).unwrap();
+                value.to_le_bytes().into_iter().take(size)
+            })
+            .collect::<Vec<_>>();
+
+        let bytes_per_line = usize::try_from((bitmap.width + 7) / 8).unwrap();
+        let padded = actual.len() / usize::try_from(bitmap.height).unwrap();
+        prop_assert_eq!(padded % size, 0);
+        for (row, expected) in actual.chunks(padded).zip(expected.chunks(bytes_per_line)) {
+            prop_assert_eq!(&row[..bytes_per_line], expected);
+            prop_assert!(row[bytes_per_line..].iter().all(|&b| b == 0));
+        }
+    }
+
+    #[test]
+    fn encode_iter_and_set_pixels(bitmap in bitmap()) {
+        let width = usize::try_from(bitmap.width).unwrap();
+
+        let mut buf = Vec::new();
+        let encoder = Encoder::new(&mut buf);
+        encoder
+            .encode_iter(
+                bitmap.pixels.iter().map(|&p| p == 1),
+                "image",
+                bitmap.width,
+                bitmap.height,
+                None,
+                None,
+            )
+            .unwrap();
+
+        let expected = bitmap
+            .pixels
+            .iter()
+            .enumerate()
+            .filter(|&(_, &p)| p == 1)
+            .map(|(i, _)| (u32::try_from(i % width).unwrap(), u32::try_from(i / width).unwrap()))
+            .collect::<Vec<_>>();
+        let actual = Decoder::from_bytes(&buf)
+            .unwrap()
+            .set_pixels()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        prop_assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,54 @@
+// SPDX-FileCopyrightText: 2024 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{
+    fs::{self, File},
+    io::{BufReader, Cursor},
+};
+
+use xbm::decode::Error;
+
+#[test]
+fn transcode() {
+    for path in [
+        "tests/data/basic.xbm",
+        "tests/data/16x14.xbm",
+        "tests/data/crc32.xbm",
+        "tests/data/hotspot.xbm",
+        "tests/data/leading_comment.xbm",
+        "tests/data/name.xbm",
+        "tests/data/qr_code.xbm",
+        "tests/data/width_7.xbm",
+        "tests/data/width_14.xbm",
+        "tests/data/zero_hotspot.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let mut buf = Vec::new();
+        xbm::transcode(reader, &mut buf).unwrap();
+        assert_eq!(buf, fs::read(path).unwrap(), "{path}");
+    }
+}
+
+#[test]
+fn transcode_normalized() {
+    for path in [
+        "tests/data/basic_lower_hex.xbm",
+        "tests/data/basic_minified.xbm",
+        "tests/data/basic_upper_prefix.xbm",
+        "tests/data/without_unsigned.xbm",
+    ] {
+        let reader = File::open(path).map(BufReader::new).unwrap();
+        let mut buf = Vec::with_capacity(132);
+        xbm::transcode(reader, &mut buf).unwrap();
+        assert_eq!(buf, include_bytes!("data/basic.xbm"), "{path}");
+    }
+}
+
+#[test]
+fn transcode_from_invalid_image() {
+    let mut buf = Vec::new();
+    let err = xbm::transcode(Cursor::new(""), &mut buf).unwrap_err();
+    assert!(matches!(err, Error::InvalidHeader { .. }));
+    assert!(buf.is_empty());
+}